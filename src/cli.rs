@@ -1,4 +1,5 @@
-use clap::{Parser, ValueHint};
+use crate::output::OutputFormat;
+use clap::{Parser, Subcommand, ValueHint};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -10,18 +11,76 @@ use std::path::PathBuf;
     long_about = "dotenvcrab is a blazing fast, portable, and safe CLI tool that validates .env files against JSON schema definitions."
 )]
 pub struct Cli {
-    #[arg(short, long, default_value = ".env", value_hint = ValueHint::FilePath)]
-    pub env: PathBuf,
+    #[command(subcommand)]
+    pub command: Option<Command>,
 
-    #[arg(short, long, default_value = "env.schema.json", value_hint = ValueHint::FilePath)]
+    #[arg(
+        short,
+        long,
+        global = true,
+        default_value = ".env",
+        value_delimiter = ',',
+        value_hint = ValueHint::FilePath
+    )]
+    pub env: Vec<PathBuf>,
+
+    #[arg(short, long, global = true)]
+    pub mode: Option<String>,
+
+    #[arg(short, long, global = true, default_value = "env.schema.json", value_hint = ValueHint::FilePath)]
     pub schema: PathBuf,
 
-    #[arg(short = 'x', long)]
+    #[arg(long, global = true, conflicts_with_all = ["env", "mode"])]
+    pub from_process_env: bool,
+
+    #[arg(long, global = true, value_delimiter = ',', requires = "from_process_env")]
+    pub prefix: Vec<String>,
+
+    #[arg(long, global = true, value_delimiter = ',', requires = "from_process_env")]
+    pub allow: Vec<String>,
+
+    #[arg(short = 'x', long, global = true)]
     pub strict: bool,
 
-    #[arg(short, long)]
+    #[arg(short, long, global = true, conflicts_with = "format")]
     pub json: bool,
 
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[arg(short, long, value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+
     #[arg(short, long)]
     pub generate: bool,
+
+    #[arg(short, long, requires = "generate")]
+    pub force: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate a commented .env.example from the schema
+    GenerateExample {
+        #[arg(short, long, default_value = ".env.example", value_hint = ValueHint::FilePath)]
+        output: PathBuf,
+
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Print the schema with every $extends and $ref resolved
+    ResolveSchema,
+
+    /// Print the schema as a draft 2020-12 JSON Schema
+    ExportJsonschema,
+
+    /// Check the schema file itself for mistakes
+    LintSchema,
+
+    /// Validate the .env and run a command with the validated variables
+    Run {
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
 }
@@ -0,0 +1,122 @@
+use crate::expr::{Expr, ExprError};
+use crate::schema::{Schema, SchemaField};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Deref;
+use thiserror::Error;
+
+/// a part of a schema that could not be compiled
+#[derive(Debug, Error)]
+pub enum CompileError {
+    #[error("invalid regex pattern for {0}: {1}")]
+    InvalidPattern(String, String),
+
+    #[error("invalid constraint {1} for {0}: {2}")]
+    InvalidExpression(String, String, ExprError),
+}
+
+impl CompileError {
+    /// the schema key the error is about
+    pub fn key(&self) -> &str {
+        match self {
+            CompileError::InvalidPattern(key, _) | CompileError::InvalidExpression(key, ..) => key,
+        }
+    }
+}
+
+/// a schema prepared for validation, with every `pattern` compiled and every
+/// constraint expression parsed up front
+///
+/// compiling once and reusing the result avoids rebuilding the same regexes
+/// and expressions for every file in a batch or every reload of a
+/// long-running process. it dereferences to the plain `Schema`.
+#[derive(Debug, Clone, Default)]
+pub struct CompiledSchema {
+    schema: Schema,
+    patterns: HashMap<String, Regex>,
+    expressions: HashMap<String, Expr>,
+}
+
+impl CompiledSchema {
+    /// compile every pattern in `schema`, including those of array items, and
+    /// parse every constraint expression
+    ///
+    /// a pattern or expression that does not compile is returned as an error,
+    /// in order of key, and left out of the compiled schema; validating
+    /// against such a schema reports it again as `InvalidRegexPattern` or
+    /// `InvalidExpression`. loading a schema with `schema::load_schema`
+    /// rejects these errors, so a loaded schema has everything compiled.
+    pub fn compile(schema: Schema) -> (Self, Vec<CompileError>) {
+        let mut patterns = HashMap::new();
+        let mut expressions = HashMap::new();
+        let mut errors = Vec::new();
+        let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
+        for (key, field) in sorted {
+            compile_field(key, field, &mut patterns, &mut errors);
+            for constraint in &field.rules().constraints {
+                if expressions.contains_key(&constraint.expr) {
+                    continue;
+                }
+                match Expr::parse(&constraint.expr) {
+                    Ok(expr) => {
+                        expressions.insert(constraint.expr.clone(), expr);
+                    }
+                    Err(error) => errors.push(CompileError::InvalidExpression(
+                        key.clone(),
+                        constraint.expr.clone(),
+                        error,
+                    )),
+                }
+            }
+        }
+
+        (Self { schema, patterns, expressions }, errors)
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn into_schema(self) -> Schema {
+        self.schema
+    }
+
+    /// the compiled regex of every pattern, keyed by its source
+    pub fn patterns(&self) -> &HashMap<String, Regex> {
+        &self.patterns
+    }
+
+    /// the parsed form of every constraint expression, keyed by its source
+    pub fn expressions(&self) -> &HashMap<String, Expr> {
+        &self.expressions
+    }
+}
+
+impl Deref for CompiledSchema {
+    type Target = Schema;
+
+    fn deref(&self) -> &Schema {
+        &self.schema
+    }
+}
+
+fn compile_field(
+    key: &str,
+    field: &SchemaField,
+    patterns: &mut HashMap<String, Regex>,
+    errors: &mut Vec<CompileError>,
+) {
+    match field {
+        SchemaField::String {
+            pattern: Some(pattern),
+            ..
+        } if !patterns.contains_key(pattern) => match Regex::new(pattern) {
+            Ok(regex) => {
+                patterns.insert(pattern.clone(), regex);
+            }
+            Err(err) => errors.push(CompileError::InvalidPattern(key.to_string(), err.to_string())),
+        },
+        SchemaField::Array { items: Some(items), .. } => compile_field(key, items, patterns, errors),
+        _ => {}
+    }
+}
@@ -0,0 +1,247 @@
+use crate::location::Location;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// a syntax error in a schema document, at the line and column of the problem
+#[derive(Debug, Error)]
+#[error("{1}")]
+pub struct DocumentError(pub Location, pub String);
+
+/// the file formats a schema can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl DocumentFormat {
+    /// pick the format from the file extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => DocumentFormat::Yaml,
+            Some("toml") => DocumentFormat::Toml,
+            _ => DocumentFormat::Json,
+        }
+    }
+}
+
+/// parse a schema document into a JSON value, whatever format it is in
+///
+/// syntax errors are reported with the line and column of the problem.
+pub fn parse_document(content: &str, path: &Path) -> Result<Value, DocumentError> {
+    match DocumentFormat::from_path(path) {
+        DocumentFormat::Json => serde_json::from_str(content).map_err(|err| {
+            let location = Location::new(path, err.line(), err.column());
+            DocumentError(location, strip_position(&err.to_string()))
+        }),
+        DocumentFormat::Yaml => serde_norway::from_str(content).map_err(|err| {
+            let location = match err.location() {
+                Some(position) => Location::new(path, position.line(), position.column()),
+                None => Location::new(path, 1, 1),
+            };
+            DocumentError(location, strip_position(&err.to_string()))
+        }),
+        DocumentFormat::Toml => toml::from_str(content).map_err(|err| {
+            let offset = err.span().map_or(0, |span| span.start);
+            let location = location_at(content, offset, path);
+            DocumentError(location, err.message().to_string())
+        }),
+    }
+}
+
+/// find the position of every top-level key of a schema document
+pub fn locate_document_keys(content: &str, path: &Path) -> HashMap<String, Location> {
+    match DocumentFormat::from_path(path) {
+        DocumentFormat::Json => locate_keys(content, path),
+        DocumentFormat::Yaml => locate_yaml_keys(content, path),
+        DocumentFormat::Toml => locate_toml_keys(content, path),
+    }
+}
+
+/// find the position of every key of the top-level JSON object
+///
+/// positions point at the opening quote of the key. the scan is lenient and
+/// only meant for reporting; the document is expected to have been parsed
+/// successfully already.
+pub fn locate_keys(content: &str, file: &Path) -> HashMap<String, Location> {
+    let mut locations = HashMap::new();
+    let chars: Vec<char> = content.chars().collect();
+    let mut depth = 0usize;
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut i = 0;
+    
+    while i < chars.len() {
+        match chars[i] {
+            '\n' => {
+                line += 1;
+                line_start = i + 1;
+            }
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            '"' => {
+                let start = i;
+                let column = i - line_start + 1;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                
+                let literal: String = chars[start..(i + 1).min(chars.len())].iter().collect();
+                let mut next = i + 1;
+                while next < chars.len() && chars[next].is_whitespace() {
+                    next += 1;
+                }
+                
+                if depth == 1
+                    && chars.get(next) == Some(&':')
+                    && let Ok(key) = serde_json::from_str::<String>(&literal)
+                {
+                    locations.insert(key, Location::new(file, line, column));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    
+    locations
+}
+
+/// find where each of `names` is declared inside the `properties` of a
+/// JSON Schema document, in any format
+///
+/// like the other locators this is a lenient line scan: it takes the first
+/// line that starts with the name as a key, or with a `[properties.NAME]`
+/// table header.
+pub fn locate_property_keys<'a>(
+    content: &str,
+    path: &Path,
+    names: impl Iterator<Item = &'a String>,
+) -> HashMap<String, Location> {
+    let mut locations = HashMap::new();
+
+    for name in names {
+        let prefixes = [
+            format!("\"{}\"", name),
+            format!("'{}'", name),
+            format!("{}:", name),
+            format!("[properties.{}]", name),
+        ];
+        let found = content.lines().enumerate().find_map(|(index, line)| {
+            let trimmed = line.trim_start();
+            prefixes
+                .iter()
+                .any(|prefix| trimmed.starts_with(prefix.as_str()))
+                .then(|| Location::new(path, index + 1, line.len() - trimmed.len() + 1))
+        });
+        if let Some(location) = found {
+            locations.insert(name.clone(), location);
+        }
+    }
+
+    locations
+}
+
+/// find the first use of `key` as a key at or after `start`, such as a key
+/// inside the field that begins there
+///
+/// quoted keys are found anywhere on a line, so that fields written on one
+/// line are covered; bare YAML and TOML keys must start the line.
+pub fn locate_key_after(content: &str, start: &Location, key: &str) -> Option<Location> {
+    let quoted = [format!("\"{}\"", key), format!("'{}'", key)];
+    let bare = [format!("{}:", key), format!("{} =", key), format!("{}=", key)];
+
+    content
+        .lines()
+        .enumerate()
+        .skip(start.line.saturating_sub(1))
+        .find_map(|(index, line)| {
+            let trimmed = line.trim_start();
+            let column = quoted
+                .iter()
+                .find_map(|quoted| line.find(quoted.as_str()))
+                .or_else(|| {
+                    bare.iter()
+                        .any(|bare| trimmed.starts_with(bare.as_str()))
+                        .then(|| line.len() - trimmed.len())
+                })?;
+            Some(Location::new(&start.file, index + 1, column + 1))
+        })
+}
+
+/// top-level YAML keys are the `KEY:` lines that are not indented
+fn locate_yaml_keys(content: &str, path: &Path) -> HashMap<String, Location> {
+    let mut locations = HashMap::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.starts_with([' ', '\t', '#', '-']) || line.starts_with("---") {
+            continue;
+        }
+        let key = match line.chars().next() {
+            Some(quote @ ('"' | '\'')) => line[1..].split(quote).next(),
+            _ => line.split(':').next().filter(|_| line.contains(':')),
+        };
+        if let Some(key) = key.map(str::trim).filter(|key| !key.is_empty()) {
+            locations.insert(key.to_string(), Location::new(path, index + 1, 1));
+        }
+    }
+
+    locations
+}
+
+/// top-level TOML keys are `[KEY]` table headers, or `KEY = ...` lines that
+/// come before the first table
+fn locate_toml_keys(content: &str, path: &Path) -> HashMap<String, Location> {
+    let mut locations = HashMap::new();
+    let mut in_table = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let column = line.len() - trimmed.len() + 1;
+
+        let key = if let Some(header) = trimmed.strip_prefix('[') {
+            in_table = true;
+            if header.starts_with('[') {
+                continue;
+            }
+            header.split([']', '.']).next()
+        } else if !in_table && trimmed.contains('=') && !trimmed.starts_with('#') {
+            trimmed.split(['=', '.']).next()
+        } else {
+            continue;
+        };
+
+        let key = key.map(|key| key.trim().trim_matches(['"', '\'']));
+        if let Some(key) = key.filter(|key| !key.is_empty()) {
+            locations
+                .entry(key.to_string())
+                .or_insert_with(|| Location::new(path, index + 1, column));
+        }
+    }
+
+    locations
+}
+
+/// drop the ` at line X column Y` suffix that JSON and YAML errors carry,
+/// since the location is reported separately
+fn strip_position(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// the line and column of a byte offset in `content`
+fn location_at(content: &str, offset: usize, path: &Path) -> Location {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(before.len(), |newline| before.len() - newline - 1) + 1;
+    Location::new(path, line, column)
+}
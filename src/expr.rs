@@ -0,0 +1,355 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExprError {
+    #[error("syntax error at position {0}: {1}")]
+    Syntax(usize, String),
+
+    #[error("type error: {0}")]
+    Type(String),
+
+    #[error("unknown function: {0}")]
+    UnknownFunction(String),
+
+    #[error("variable {0} is not set")]
+    Unset(String),
+}
+
+/// a value an expression works with
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// a parsed constraint expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    Var(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// functions that can be called from an expression, with their arity
+const FUNCTIONS: [(&str, usize); 4] = [
+    ("startsWith", 2),
+    ("endsWith", 2),
+    ("contains", 2),
+    ("len", 1),
+];
+
+impl Expr {
+    /// parse an expression such as `POOL_MIN <= POOL_MAX`
+    ///
+    /// the language has number, string and boolean literals, variable names,
+    /// comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`), `&&`, `||`, `!`,
+    /// parentheses, and the functions `startsWith`, `endsWith`, `contains`
+    /// and `len`.
+    pub fn parse(source: &str) -> Result<Expr, ExprError> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unexpected input"));
+        }
+        Ok(expr)
+    }
+
+    /// evaluate the expression, reading variables through `lookup`
+    ///
+    /// `&&` and `||` short-circuit, so a variable that is only read on the
+    /// branch not taken does not need to be set.
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<Value>) -> Result<Value, ExprError> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Var(name) => lookup(name).ok_or_else(|| ExprError::Unset(name.clone())),
+            Expr::Not(inner) => Ok(Value::Bool(!inner.eval_bool(lookup)?)),
+            Expr::And(left, right) => {
+                Ok(Value::Bool(left.eval_bool(lookup)? && right.eval_bool(lookup)?))
+            }
+            Expr::Or(left, right) => {
+                Ok(Value::Bool(left.eval_bool(lookup)? || right.eval_bool(lookup)?))
+            }
+            Expr::Compare(op, left, right) => {
+                let (left, right) = (left.eval(lookup)?, right.eval(lookup)?);
+                compare(*op, &left, &right).map(Value::Bool)
+            }
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(lookup))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call(name, &args)
+            }
+        }
+    }
+
+    /// evaluate the expression and require a boolean result
+    pub fn eval_bool(&self, lookup: &dyn Fn(&str) -> Option<Value>) -> Result<bool, ExprError> {
+        match self.eval(lookup)? {
+            Value::Bool(b) => Ok(b),
+            other => Err(ExprError::Type(format!("expected boolean, got {}", other.type_name()))),
+        }
+    }
+}
+
+fn compare(op: CompareOp, left: &Value, right: &Value) -> Result<bool, ExprError> {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => {
+            return Err(ExprError::Type(format!(
+                "cannot compare {} with {}",
+                left.type_name(),
+                right.type_name()
+            )));
+        }
+    };
+
+    if !matches!(op, CompareOp::Eq | CompareOp::Ne) && !matches!(left, Value::Number(_)) {
+        return Err(ExprError::Type(format!("cannot order {} values", left.type_name())));
+    }
+
+    let Some(ordering) = ordering else {
+        return Ok(op == CompareOp::Ne);
+    };
+    Ok(match op {
+        CompareOp::Eq => ordering.is_eq(),
+        CompareOp::Ne => ordering.is_ne(),
+        CompareOp::Lt => ordering.is_lt(),
+        CompareOp::Le => ordering.is_le(),
+        CompareOp::Gt => ordering.is_gt(),
+        CompareOp::Ge => ordering.is_ge(),
+    })
+}
+
+fn call(name: &str, args: &[Value]) -> Result<Value, ExprError> {
+    let string = |value: &Value| match value {
+        Value::String(s) => Ok(s.clone()),
+        other => Err(ExprError::Type(format!(
+            "{} expects string arguments, got {}",
+            name,
+            other.type_name()
+        ))),
+    };
+
+    match name {
+        "startsWith" => Ok(Value::Bool(string(&args[0])?.starts_with(&string(&args[1])?))),
+        "endsWith" => Ok(Value::Bool(string(&args[0])?.ends_with(&string(&args[1])?))),
+        "contains" => Ok(Value::Bool(string(&args[0])?.contains(&string(&args[1])?))),
+        "len" => Ok(Value::Number(string(&args[0])?.chars().count() as f64)),
+        _ => Err(ExprError::UnknownFunction(name.to_string())),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> ExprError {
+        ExprError::Syntax(self.pos, message.to_string())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// whether `token` comes next, ignoring leading whitespace
+    fn peek(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        token
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    /// consume `token` if it comes next
+    fn eat(&mut self, token: &str) -> bool {
+        let matches = self.peek(token);
+        if matches {
+            self.pos += token.chars().count();
+        }
+        matches
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ExprError> {
+        let mut left = self.parse_and()?;
+        while self.eat("||") {
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ExprError> {
+        let mut left = self.parse_not()?;
+        while self.eat("&&") {
+            left = Expr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, ExprError> {
+        if !self.peek("!=") && self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_compare()
+    }
+
+    fn parse_compare(&mut self) -> Result<Expr, ExprError> {
+        let left = self.parse_primary()?;
+
+        const OPS: [(&str, CompareOp); 6] = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+        for (token, op) in OPS {
+            if self.eat(token) {
+                let right = self.parse_primary()?;
+                return Ok(Expr::Compare(op, Box::new(left), Box::new(right)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ExprError> {
+        self.skip_whitespace();
+
+        match self.chars.get(self.pos) {
+            None => Err(self.error("unexpected end of expression")),
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                if !self.eat(")") {
+                    return Err(self.error("expected )"));
+                }
+                Ok(inner)
+            }
+            Some('"') | Some('\'') => self.parse_string(),
+            Some(c) if c.is_ascii_digit() || *c == '-' => self.parse_number(),
+            Some(c) if c.is_ascii_alphabetic() || *c == '_' => self.parse_name(),
+            Some(_) => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<Expr, ExprError> {
+        let quote = self.chars[self.pos];
+        self.pos += 1;
+        let mut value = String::new();
+
+        loop {
+            match self.chars.get(self.pos) {
+                None => return Err(self.error("unterminated string")),
+                Some(c) if *c == quote => {
+                    self.pos += 1;
+                    return Ok(Expr::Literal(Value::String(value)));
+                }
+                Some('\\') if self.pos + 1 < self.chars.len() => {
+                    value.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    value.push(*c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Expr, ExprError> {
+        let start = self.pos;
+        self.pos += 1;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+        {
+            self.pos += 1;
+        }
+
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse()
+            .map(|n| Expr::Literal(Value::Number(n)))
+            .map_err(|_| ExprError::Syntax(start, format!("invalid number {}", text)))
+    }
+
+    fn parse_name(&mut self) -> Result<Expr, ExprError> {
+        let start = self.pos;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+
+        match name.as_str() {
+            "true" => return Ok(Expr::Literal(Value::Bool(true))),
+            "false" => return Ok(Expr::Literal(Value::Bool(false))),
+            _ => {}
+        }
+
+        if !self.eat("(") {
+            return Ok(Expr::Var(name));
+        }
+
+        let mut args = Vec::new();
+        if !self.eat(")") {
+            loop {
+                args.push(self.parse_or()?);
+                if self.eat(")") {
+                    break;
+                }
+                if !self.eat(",") {
+                    return Err(self.error("expected , or )"));
+                }
+            }
+        }
+
+        match FUNCTIONS.iter().find(|(function, _)| *function == name) {
+            Some((_, arity)) if *arity == args.len() => Ok(Expr::Call(name, args)),
+            Some((_, arity)) => Err(ExprError::Syntax(
+                start,
+                format!("{} takes {} argument(s), got {}", name, arity, args.len()),
+            )),
+            None => Err(ExprError::UnknownFunction(name)),
+        }
+    }
+}
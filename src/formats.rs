@@ -0,0 +1,186 @@
+use crate::schema::StringFormat;
+use regex::Regex;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+static UUID_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$")
+        .unwrap()
+});
+
+static DATE_TIME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})[Tt ](\d{2}):(\d{2}):(\d{2})(\.\d+)?([Zz]|[+-](\d{2}):(\d{2}))$",
+    )
+    .unwrap()
+});
+
+/// check whether `value` is valid for a built-in string format
+pub fn matches_format(format: StringFormat, value: &str) -> bool {
+    match format {
+        StringFormat::Url => url::Url::parse(value).is_ok(),
+        StringFormat::Email => is_email(value),
+        StringFormat::Hostname => is_hostname(value),
+        StringFormat::Ipv4 => value.parse::<Ipv4Addr>().is_ok(),
+        StringFormat::Ipv6 => value.parse::<Ipv6Addr>().is_ok(),
+        StringFormat::Uuid => UUID_REGEX.is_match(value),
+        StringFormat::Port => value.parse::<u16>().is_ok_and(|port| port > 0),
+        StringFormat::Duration => parse_duration(value).is_some(),
+        StringFormat::DateTime => is_date_time(value),
+    }
+}
+
+/// parse a duration such as `250ms`, `30s`, `5m` or `1h30m`
+///
+/// a duration is one or more `<number><unit>` parts, where the unit is one of
+/// `ns`, `us`, `µs`, `ms`, `s`, `m`, `h` or `d`. numbers may have a fraction,
+/// and each part is rounded to the nearest nanosecond.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    const UNITS: [(&str, u128); 8] = [
+        ("ns", 1),
+        ("us", 1_000),
+        ("µs", 1_000),
+        ("ms", 1_000_000),
+        ("s", 1_000_000_000),
+        ("m", 60_000_000_000),
+        ("h", 3_600_000_000_000),
+        ("d", 86_400_000_000_000),
+    ];
+
+    let mut rest = value;
+    let mut nanos: u128 = 0;
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number = &rest[..number_len];
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let (_, factor) = UNITS.iter().find(|(unit, _)| *unit == &rest[..unit_len])?;
+        rest = &rest[unit_len..];
+
+        nanos = nanos.checked_add(scale(number, *factor)?)?;
+    }
+
+    let seconds = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(seconds, (nanos % 1_000_000_000) as u32))
+}
+
+/// parse a byte size such as `512MiB`, `2GB` or `1024` into a number of bytes
+///
+/// decimal units (`KB`, `MB`, `GB`, `TB`) are powers of 1000 and binary units
+/// (`KiB`, `MiB`, `GiB`, `TiB`) powers of 1024. units are case-insensitive, a
+/// bare number is in bytes, and fractions are rounded to the nearest byte.
+pub fn parse_byte_size(value: &str) -> Option<u64> {
+    const UNITS: [(&str, u128); 9] = [
+        ("b", 1),
+        ("kb", 1_000),
+        ("mb", 1_000_000),
+        ("gb", 1_000_000_000),
+        ("tb", 1_000_000_000_000),
+        ("kib", 1 << 10),
+        ("mib", 1 << 20),
+        ("gib", 1 << 30),
+        ("tib", 1 << 40),
+    ];
+
+    let value = value.trim();
+    let number_len = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let unit = value[number_len..].trim_start().to_lowercase();
+
+    let factor = if unit.is_empty() {
+        1
+    } else {
+        UNITS.iter().find(|(name, _)| *name == unit)?.1
+    };
+
+    u64::try_from(scale(&value[..number_len], factor)?).ok()
+}
+
+/// multiply a decimal number such as `1.25` by `factor` without going through
+/// floating point, rounding half up to a whole number
+///
+/// digits past the 24th after the point are ignored, which keeps the product
+/// of the fraction and any unit factor within a `u128`.
+fn scale(number: &str, factor: u128) -> Option<u128> {
+    const MAX_FRACTION_DIGITS: usize = 24;
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !digits(whole) || !digits(fraction) {
+        return None;
+    }
+
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let fraction = &fraction[..fraction.len().min(MAX_FRACTION_DIGITS)];
+    let scaled = whole.checked_mul(factor)?;
+    if fraction.is_empty() {
+        return Some(scaled);
+    }
+
+    let denominator = 10u128.pow(fraction.len() as u32);
+    let numerator = fraction.parse::<u128>().ok()? * factor;
+    let rounded = (numerator + denominator / 2) / denominator;
+    scaled.checked_add(rounded)
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && local.len() <= 64
+        && !local.contains(|c: char| c.is_whitespace() || c == '@')
+        && domain.contains('.')
+        && is_hostname(domain)
+}
+
+fn is_hostname(value: &str) -> bool {
+    let value = value.strip_suffix('.').unwrap_or(value);
+
+    !value.is_empty()
+        && value.len() <= 253
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+fn is_date_time(value: &str) -> bool {
+    let Some(captures) = DATE_TIME_REGEX.captures(value) else {
+        return false;
+    };
+    let part = |i: usize| -> u32 { captures.get(i).map_or(0, |m| m.as_str().parse().unwrap_or(0)) };
+
+    let (year, month, day) = (part(1), part(2), part(3));
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+        && part(4) < 24
+        && part(5) < 60
+        && part(6) <= 60
+        && part(9) < 24
+        && part(10) < 60
+}
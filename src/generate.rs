@@ -0,0 +1,166 @@
+use crate::schema::{FieldRules, Schema, SchemaField};
+use crate::validation::is_boolean_literal;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GenerateError {
+    #[error("{0} already exists (use --force to overwrite)")]
+    AlreadyExists(PathBuf),
+
+    #[error("failed to write file: {0}")]
+    WriteError(#[from] std::io::Error),
+
+    #[error("failed to serialize schema: {0}")]
+    SerializeError(#[from] serde_json::Error),
+}
+
+/// infer a schema field from a single .env value
+///
+/// values that parse as a finite number become `number`, values accepted by
+/// the boolean validator become `boolean`, and everything else is a `string`.
+/// every inferred field is marked as required.
+pub fn infer_field(value: &str) -> SchemaField {
+    if value.parse::<f64>().is_ok_and(f64::is_finite) {
+        SchemaField::Number {
+            required: true,
+            default: None,
+            description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
+        }
+    } else if is_boolean_literal(value) {
+        SchemaField::Boolean {
+            required: true,
+            default: None,
+            description: None,
+            rules: FieldRules::default(),
+        }
+    } else {
+        SchemaField::String {
+            required: true,
+            default: None,
+            description: None,
+            pattern: None,
+            min_length: None,
+            max_length: None,
+            format: None,
+            rules: FieldRules::default(),
+        }
+    }
+}
+
+/// build a schema with one inferred field per environment variable
+pub fn generate_schema(env_vars: &HashMap<String, String>) -> Schema {
+    env_vars
+        .iter()
+        .map(|(key, value)| (key.clone(), infer_field(value)))
+        .collect()
+}
+
+/// serialize a schema as pretty-printed JSON with keys in sorted order
+pub fn schema_to_json(schema: &Schema) -> Result<String, serde_json::Error> {
+    let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
+    serde_json::to_string_pretty(&sorted)
+}
+
+/// write a schema to `path`, refusing to replace an existing file unless `force` is set
+pub fn write_schema<P: AsRef<Path>>(
+    schema: &Schema,
+    path: P,
+    force: bool,
+) -> Result<(), GenerateError> {
+    let json = schema_to_json(schema)?;
+    write_file(path.as_ref(), &(json + "\n"), force)
+}
+
+/// render a commented `.env.example` from a schema
+///
+/// keys are emitted in sorted order. each entry is preceded by its description
+/// and a hint listing enum values or the string pattern. defaults are filled in,
+/// and required keys without a default are left empty with a `required` marker.
+pub fn generate_example(schema: &Schema) -> String {
+    let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
+    let mut entries = Vec::new();
+
+    for (key, field) in sorted {
+        let mut entry = String::new();
+
+        if let Some(description) = field.description() {
+            for line in description.lines() {
+                entry.push_str(&format!("# {}\n", line));
+            }
+        }
+
+        match field {
+            SchemaField::Enum { values, .. } => {
+                entry.push_str(&format!("# one of: {}\n", values.join(", ")));
+            }
+            SchemaField::String { pattern: Some(pattern), .. } => {
+                entry.push_str(&format!("# pattern: {}\n", pattern));
+            }
+            SchemaField::Url { schemes, .. } if !schemes.is_empty() => {
+                entry.push_str(&format!("# url with scheme: {}\n", schemes.join(", ")));
+            }
+            SchemaField::Array { separator, .. } => {
+                entry.push_str(&format!("# list separated by '{}'\n", separator));
+            }
+            _ => {}
+        }
+
+        let default = field.get_default();
+        if field.is_required() && default.is_none() {
+            entry.push_str("# (required)\n");
+        }
+
+        let value = default.map(|d| quote_value(&d)).unwrap_or_default();
+        entry.push_str(&format!("{}={}\n", key, value));
+        entries.push(entry);
+    }
+
+    entries.join("\n")
+}
+
+/// write a `.env.example` generated from `schema` to `path`
+pub fn write_example<P: AsRef<Path>>(
+    schema: &Schema,
+    path: P,
+    force: bool,
+) -> Result<(), GenerateError> {
+    write_file(path.as_ref(), &generate_example(schema), force)
+}
+
+fn write_file(path: &Path, contents: &str, force: bool) -> Result<(), GenerateError> {
+    if path.exists() && !force {
+        return Err(GenerateError::AlreadyExists(path.to_path_buf()));
+    }
+
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+fn quote_value(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '#' | '"' | '\'' | '$' | '\\'));
+
+    if !needs_quotes {
+        value.to_string()
+    } else if !value.contains('\'') {
+        format!("'{}'", value)
+    } else {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('$', "\\$");
+        format!("\"{}\"", escaped)
+    }
+}
@@ -0,0 +1,527 @@
+use crate::schema::{Schema, SchemaField, StringFormat};
+use crate::validation::BOOLEAN_LITERALS;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// a node in a JSON document that failed a JSON Schema keyword
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// JSON pointer to the failing node, empty for the document root
+    pub pointer: String,
+    pub message: String,
+}
+
+/// check `instance` against a JSON Schema and return every violation
+///
+/// only a subset of draft 2020-12 is supported: `type`, `enum`, `const`,
+/// `properties`, `required`, `additionalProperties`, `items`, `prefixItems`,
+/// `minItems`, `maxItems`, `uniqueItems`, `minProperties`, `maxProperties`,
+/// `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`, `multipleOf`,
+/// `minLength`, `maxLength`, `pattern`, `allOf`, `anyOf`, `oneOf` and `not`.
+/// other keywords are ignored.
+pub fn check(schema: &Value, instance: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    check_node(schema, instance, "", &mut violations);
+    violations
+}
+
+fn check_node(schema: &Value, instance: &Value, pointer: &str, out: &mut Vec<Violation>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            out.push(violation(pointer, "no value is allowed here".to_string()));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|name| has_type(instance, name)) {
+            out.push(violation(
+                pointer,
+                format!("expected {}, got {}", types.join(" or "), type_name(instance)),
+            ));
+            return;
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum")
+        && !allowed.iter().any(|value| json_equal(value, instance))
+    {
+        out.push(violation(pointer, format!("expected one of {}, got {}", Value::Array(allowed.clone()), instance)));
+    }
+    if let Some(expected) = schema.get("const")
+        && !json_equal(expected, instance)
+    {
+        out.push(violation(pointer, format!("expected {}, got {}", expected, instance)));
+    }
+
+    match instance {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        out.push(violation(pointer, format!("missing required property {}", name)));
+                    }
+                }
+            }
+            if let Some(min) = schema.get("minProperties").and_then(Value::as_u64)
+                && (object.len() as u64) < min
+            {
+                out.push(violation(pointer, format!("expected at least {} properties, got {}", min, object.len())));
+            }
+            if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64)
+                && object.len() as u64 > max
+            {
+                out.push(violation(pointer, format!("expected at most {} properties, got {}", max, object.len())));
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, value) in object {
+                let child = child_pointer(pointer, name);
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(property) => check_node(property, value, &child, out),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            out.push(violation(&child, "property is not allowed".to_string()));
+                        }
+                        Some(additional) => check_node(additional, value, &child, out),
+                        None => {}
+                    },
+                }
+            }
+        }
+
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64)
+                && (items.len() as u64) < min
+            {
+                out.push(violation(pointer, format!("expected at least {} items, got {}", min, items.len())));
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64)
+                && items.len() as u64 > max
+            {
+                out.push(violation(pointer, format!("expected at most {} items, got {}", max, items.len())));
+            }
+            if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+                let mut seen = HashSet::new();
+                for (index, item) in items.iter().enumerate() {
+                    if !seen.insert(item.to_string()) {
+                        out.push(violation(&child_pointer(pointer, &index.to_string()), "duplicate item".to_string()));
+                    }
+                }
+            }
+
+            let prefix = schema.get("prefixItems").and_then(Value::as_array);
+            let prefix_len = prefix.map_or(0, Vec::len);
+            for (index, item) in items.iter().enumerate() {
+                let child = child_pointer(pointer, &index.to_string());
+                if let Some(item_schema) = prefix.and_then(|prefix| prefix.get(index)) {
+                    check_node(item_schema, item, &child, out);
+                } else if index >= prefix_len
+                    && let Some(item_schema) = schema.get("items")
+                {
+                    check_node(item_schema, item, &child, out);
+                }
+            }
+        }
+
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or(f64::NAN);
+            let bound = |name: &str| schema.get(name).and_then(Value::as_f64);
+
+            if let Some(min) = bound("minimum")
+                && number < min
+            {
+                out.push(violation(pointer, format!("expected at least {}, got {}", min, number)));
+            }
+            if let Some(max) = bound("maximum")
+                && number > max
+            {
+                out.push(violation(pointer, format!("expected at most {}, got {}", max, number)));
+            }
+            if let Some(min) = bound("exclusiveMinimum")
+                && number <= min
+            {
+                out.push(violation(pointer, format!("expected greater than {}, got {}", min, number)));
+            }
+            if let Some(max) = bound("exclusiveMaximum")
+                && number >= max
+            {
+                out.push(violation(pointer, format!("expected less than {}, got {}", max, number)));
+            }
+            if let Some(step) = bound("multipleOf")
+                && (step == 0.0 || ((number / step) - (number / step).round()).abs() >= 1e-9)
+            {
+                out.push(violation(pointer, format!("expected a multiple of {}, got {}", step, number)));
+            }
+        }
+
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+                && length < min
+            {
+                out.push(violation(pointer, format!("expected at least {} characters, got {}", min, length)));
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+                && length > max
+            {
+                out.push(violation(pointer, format!("expected at most {} characters, got {}", max, length)));
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(regex) if !regex.is_match(text) => {
+                        out.push(violation(pointer, format!("does not match pattern {}", pattern)));
+                    }
+                    Ok(_) => {}
+                    Err(err) => out.push(violation(pointer, format!("invalid pattern {}: {}", pattern, err))),
+                }
+            }
+        }
+
+        _ => {}
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get("allOf") {
+        for subschema in schemas {
+            check_node(subschema, instance, pointer, out);
+        }
+    }
+    if let Some(Value::Array(schemas)) = schema.get("anyOf")
+        && !schemas.iter().any(|subschema| matches(subschema, instance))
+    {
+        out.push(violation(pointer, "does not match any schema in anyOf".to_string()));
+    }
+    if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+        let matching = schemas.iter().filter(|subschema| matches(subschema, instance)).count();
+        if matching != 1 {
+            out.push(violation(
+                pointer,
+                format!("expected exactly one schema in oneOf to match, {} did", matching),
+            ));
+        }
+    }
+    if let Some(subschema) = schema.get("not")
+        && matches(subschema, instance)
+    {
+        out.push(violation(pointer, "must not match the schema in not".to_string()));
+    }
+}
+
+/// whether a schema document is standard JSON Schema rather than the flat
+/// format of `schema::SchemaField`
+///
+/// a document counts as JSON Schema when it declares `$schema`, or when it is
+/// `"type": "object"` with a `properties` object.
+pub fn is_json_schema(document: &Map<String, Value>) -> bool {
+    document.get("$schema").is_some_and(Value::is_string)
+        || (document.get("type").and_then(Value::as_str) == Some("object")
+            && document.get("properties").is_some_and(Value::is_object))
+}
+
+/// translate a JSON Schema document describing an object of variables into
+/// flat schema fields
+///
+/// `properties`, `required`, `type`, `enum`, `default`, `description`,
+/// `pattern`, `minLength`, `maxLength`, `format`, `minimum`, `maximum`,
+/// `exclusiveMinimum`, `exclusiveMaximum` and `multipleOf` are mapped, and
+/// the annotations `title`, `examples` and `$comment` are dropped. anything
+/// else is returned as the JSON pointers of the keywords that could not be
+/// mapped, so that no part of the document is silently ignored.
+pub fn translate(document: &Map<String, Value>) -> Result<Map<String, Value>, Vec<String>> {
+    let mut unmapped = Vec::new();
+    
+    for (keyword, value) in document {
+        let mapped = match keyword.as_str() {
+            "$schema" | "$id" | "$comment" | "title" | "description" => true,
+            "type" => value.as_str() == Some("object"),
+            "properties" => value.is_object(),
+            "required" => value.as_array().is_some_and(|names| names.iter().all(Value::is_string)),
+            _ => false,
+        };
+        if !mapped {
+            unmapped.push(child_pointer("", keyword));
+        }
+    }
+    
+    let mut fields = Map::new();
+    if let Some(Value::Object(properties)) = document.get("properties") {
+        for (name, property) in properties {
+            let pointer = child_pointer("/properties", name);
+            let field = translate_property(property, &pointer, &mut unmapped);
+            fields.insert(name.clone(), Value::Object(field));
+        }
+    }
+    
+    if let Some(Value::Array(required)) = document.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            let field = fields
+                .entry(name)
+                .or_insert_with(|| serde_json::json!({ "type": "string" }));
+            if let Value::Object(field) = field {
+                field.insert("required".to_string(), Value::Bool(true));
+            }
+        }
+    }
+    
+    if unmapped.is_empty() {
+        Ok(fields)
+    } else {
+        Err(unmapped)
+    }
+}
+
+fn translate_property(property: &Value, pointer: &str, unmapped: &mut Vec<String>) -> Map<String, Value> {
+    let mut field = Map::new();
+    let Value::Object(property) = property else {
+        unmapped.push(pointer.to_string());
+        return field;
+    };
+    
+    let kind = match property.get("enum") {
+        Some(values) if scalar_strings(values).is_some() => "enum",
+        _ => property.get("type").and_then(Value::as_str).unwrap_or("string"),
+    };
+    
+    for (keyword, value) in property {
+        let (name, value) = match (keyword.as_str(), kind) {
+            ("type", _) if matches!(value.as_str(), Some("string" | "number" | "integer" | "boolean")) => continue,
+            ("title" | "examples" | "$comment", _) => continue,
+            ("description", _) if value.is_string() => ("description", value.clone()),
+            ("enum", "enum") => ("values", Value::from(scalar_strings(value))),
+            ("default", "string" | "enum") if scalar_string(value).is_some() => {
+                ("default", Value::from(scalar_string(value)))
+            }
+            ("default", "number" | "integer") if value.is_number() => ("default", value.clone()),
+            ("default", "boolean") if value.is_boolean() => ("default", value.clone()),
+            ("pattern", "string") if value.is_string() => ("pattern", value.clone()),
+            ("minLength", "string") if value.is_u64() => ("minLength", value.clone()),
+            ("maxLength", "string") if value.is_u64() => ("maxLength", value.clone()),
+            ("format", "string") if string_format(value).is_some() => ("format", Value::from(string_format(value))),
+            (
+                "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" | "multipleOf",
+                "number" | "integer",
+            ) if value.is_number() => (keyword.as_str(), value.clone()),
+            _ => {
+                unmapped.push(child_pointer(pointer, keyword));
+                continue;
+            }
+        };
+        field.insert(name.to_string(), value);
+    }
+    
+    if kind == "integer" {
+        field.insert("type".to_string(), Value::from("number"));
+        field.insert("integer".to_string(), Value::Bool(true));
+    } else {
+        field.insert("type".to_string(), Value::from(kind));
+    }
+    field
+}
+
+/// the values of an `enum` as strings, if they are all strings, numbers or
+/// booleans
+fn scalar_strings(values: &Value) -> Option<Vec<String>> {
+    values.as_array()?.iter().map(scalar_string).collect()
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// the `StringFormat` name for a JSON Schema `format`
+fn string_format(value: &Value) -> Option<&'static str> {
+    match value.as_str()? {
+        "uri" | "url" => Some("url"),
+        "email" => Some("email"),
+        "hostname" => Some("hostname"),
+        "ipv4" => Some("ipv4"),
+        "ipv6" => Some("ipv6"),
+        "uuid" => Some("uuid"),
+        "date-time" => Some("date-time"),
+        _ => None,
+    }
+}
+
+fn matches(schema: &Value, instance: &Value) -> bool {
+    let mut violations = Vec::new();
+    check_node(schema, instance, "", &mut violations);
+    violations.is_empty()
+}
+
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "integer" => instance.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "number" => instance.is_number(),
+        _ => type_name(instance) == name,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// compare JSON values, treating `1` and `1.0` as equal like JSON Schema does
+fn json_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_equal(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| json_equal(a, b)))
+        }
+        _ => a == b,
+    }
+}
+
+/// append a reference token to a JSON pointer, escaping `~` and `/`
+fn child_pointer(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, token.replace('~', "~0").replace('/', "~1"))
+}
+
+fn violation(pointer: &str, message: String) -> Violation {
+    Violation {
+        pointer: pointer.to_string(),
+        message,
+    }
+}
+
+/// the `$schema` URI of the dialect `export` produces
+pub const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// a number as `str::parse::<f64>` reads it, without `inf` and `NaN`
+const NUMBER_PATTERN: &str = r"^[+-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)([eE][+-]?[0-9]+)?$";
+const INTEGER_PATTERN: &str = r"^[+-]?[0-9]+(\.0*)?$";
+const DURATION_PATTERN: &str = r"^(([0-9]+(\.[0-9]*)?|\.[0-9]+)(ns|us|µs|ms|s|m|h|d))+$";
+const PORT_PATTERN: &str = r"^[0-9]{1,5}$";
+const BYTE_SIZE_PATTERN: &str = r"^\s*([0-9]+(\.[0-9]*)?|\.[0-9]+)\s*(([kKmMgGtT][iI]?)?[bB])?\s*$";
+
+/// convert a schema into a draft 2020-12 JSON Schema for an object of
+/// variables
+///
+/// env values are always strings, so every property is `"type": "string"`.
+/// numbers, booleans, durations and byte sizes are described by a `pattern`
+/// instead, and bounds that a pattern cannot express, such as `minimum`, are
+/// left for dotenvcrab to check. descriptions and defaults are carried over.
+pub fn export(schema: &Schema) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    
+    let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
+    for (key, field) in sorted {
+        if field.is_required() {
+            required.push(Value::from(key.as_str()));
+        }
+        properties.insert(key.clone(), Value::Object(export_field(field)));
+    }
+    
+    let mut document = Map::new();
+    document.insert("$schema".to_string(), Value::from(DRAFT_2020_12));
+    document.insert("type".to_string(), Value::from("object"));
+    document.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        document.insert("required".to_string(), Value::Array(required));
+    }
+    Value::Object(document)
+}
+
+fn export_field(field: &SchemaField) -> Map<String, Value> {
+    let mut property = Map::new();
+    property.insert("type".to_string(), Value::from("string"));
+    if let Some(description) = field.description() {
+        property.insert("description".to_string(), Value::from(description.as_str()));
+    }
+    if let Some(default) = field.get_default() {
+        property.insert("default".to_string(), Value::from(default));
+    }
+    
+    let mut set = |keyword: &str, value: Value| {
+        property.insert(keyword.to_string(), value);
+    };
+    match field {
+        SchemaField::String {
+            pattern,
+            min_length,
+            max_length,
+            format,
+            ..
+        } => {
+            if let Some(pattern) = pattern {
+                set("pattern", Value::from(pattern.as_str()));
+            }
+            if let Some(min) = min_length {
+                set("minLength", Value::from(*min));
+            }
+            if let Some(max) = max_length {
+                set("maxLength", Value::from(*max));
+            }
+            match format {
+                Some(StringFormat::Url) => set("format", Value::from("uri")),
+                Some(StringFormat::Duration) if pattern.is_none() => {
+                    set("pattern", Value::from(DURATION_PATTERN))
+                }
+                Some(StringFormat::Port) if pattern.is_none() => set("pattern", Value::from(PORT_PATTERN)),
+                Some(StringFormat::Duration | StringFormat::Port) | None => {}
+                Some(format) => set("format", Value::from(format.name())),
+            }
+        }
+        SchemaField::Number { integer, .. } => {
+            let pattern = if *integer { INTEGER_PATTERN } else { NUMBER_PATTERN };
+            set("pattern", Value::from(pattern));
+        }
+        SchemaField::Boolean { .. } => set("pattern", Value::from(boolean_pattern())),
+        SchemaField::Enum { values, .. } => set("enum", Value::from(values.clone())),
+        SchemaField::Url { .. } => set("format", Value::from("uri")),
+        SchemaField::Array { .. } => {}
+        SchemaField::Json { schema, .. } => {
+            set("contentMediaType", Value::from("application/json"));
+            if let Some(schema) = schema {
+                set("contentSchema", schema.clone());
+            }
+        }
+        SchemaField::Duration { .. } => set("pattern", Value::from(DURATION_PATTERN)),
+        SchemaField::ByteSize { .. } => set("pattern", Value::from(BYTE_SIZE_PATTERN)),
+    }
+    property
+}
+
+/// a case-insensitive pattern for the boolean literals, written without
+/// inline flags since JSON Schema patterns follow ECMA-262
+fn boolean_pattern() -> String {
+    let literals: Vec<String> = BOOLEAN_LITERALS
+        .iter()
+        .map(|literal| {
+            literal
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphabetic() {
+                        format!("[{}{}]", c.to_ascii_lowercase(), c.to_ascii_uppercase())
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    format!("^({})$", literals.join("|"))
+}
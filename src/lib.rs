@@ -1,4 +1,14 @@
 pub mod cli;
+pub mod compiled;
+pub mod document;
+pub mod expr;
+pub mod formats;
+pub mod generate;
+pub mod json_schema;
+pub mod lint;
+pub mod loader;
+pub mod location;
 pub mod output;
+pub mod run;
 pub mod schema;
 pub mod validation;
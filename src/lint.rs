@@ -0,0 +1,138 @@
+use crate::compiled::{CompileError, CompiledSchema};
+use crate::schema::{Schema, SchemaField};
+use crate::validation::{validate_compiled_value, ValidationError};
+use std::collections::{BTreeMap, HashSet};
+use thiserror::Error;
+
+/// a mistake in the schema itself, found without looking at any .env values
+#[derive(Debug, Error)]
+pub enum LintIssue {
+    #[error(transparent)]
+    Compile(#[from] CompileError),
+
+    #[error("invalid default: {1}")]
+    InvalidDefault(String, ValidationError),
+
+    #[error("enum {0} has no values")]
+    EmptyEnum(String),
+
+    #[error("enum {0} lists {1} more than once")]
+    DuplicateEnumValue(String, String),
+
+    #[error("{0} is not a valid shell variable name")]
+    InvalidKeyName(String),
+
+    #[error("{0} is required but has a default, so it can never be missing")]
+    RequiredWithDefault(String),
+}
+
+impl LintIssue {
+    /// the schema key the issue is about
+    pub fn key(&self) -> &str {
+        match self {
+            LintIssue::Compile(error) => error.key(),
+            LintIssue::InvalidDefault(key, _)
+            | LintIssue::EmptyEnum(key)
+            | LintIssue::DuplicateEnumValue(key, _)
+            | LintIssue::InvalidKeyName(key)
+            | LintIssue::RequiredWithDefault(key) => key,
+        }
+    }
+
+    /// whether the issue makes the schema unusable, rather than being a
+    /// likely mistake
+    ///
+    /// errors stop the schema from loading; the others are only warnings.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            LintIssue::Compile(..)
+                | LintIssue::InvalidDefault(..)
+                | LintIssue::EmptyEnum(..)
+        )
+    }
+}
+
+/// compile a schema and check it for mistakes, with the issues in order of
+/// key
+///
+/// the issues are the compile errors of `CompiledSchema::compile` followed,
+/// for each key, by those of `lint_schema`.
+pub fn lint(schema: Schema) -> (CompiledSchema, Vec<LintIssue>) {
+    let (compiled, errors) = CompiledSchema::compile(schema);
+    let mut issues: Vec<LintIssue> = errors.into_iter().map(LintIssue::from).collect();
+    issues.extend(lint_schema(&compiled));
+    issues.sort_by(|a, b| a.key().cmp(b.key()));
+    (compiled, issues)
+}
+
+/// check a compiled schema for mistakes, in order of key
+///
+/// every `default` is validated against its own field, enums are checked for
+/// missing and repeated values, keys must be valid shell variable names, and a
+/// required field should not have a default, since the default means it is
+/// never missing. patterns and constraint expressions are checked by
+/// `CompiledSchema::compile`, and a default is not checked when its field has
+/// a pattern that did not compile.
+pub fn lint_schema(schema: &CompiledSchema) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
+
+    for (key, field) in sorted {
+        if !is_shell_name(key) {
+            issues.push(LintIssue::InvalidKeyName(key.clone()));
+        }
+        if field.is_required() && field.get_default().is_some() {
+            issues.push(LintIssue::RequiredWithDefault(key.clone()));
+        }
+
+        lint_enums(key, field, &mut issues);
+        if let Some(default) = field.get_default()
+            && patterns_compiled(field, schema)
+        {
+            for error in validate_compiled_value(key, &default, field, schema) {
+                issues.push(LintIssue::InvalidDefault(key.clone(), error));
+            }
+        }
+    }
+
+    issues
+}
+
+/// check the enum values of a field and the fields it contains
+fn lint_enums(key: &str, field: &SchemaField, issues: &mut Vec<LintIssue>) {
+    match field {
+        SchemaField::Enum { values, .. } => {
+            if values.is_empty() {
+                issues.push(LintIssue::EmptyEnum(key.to_string()));
+            }
+            let mut seen = HashSet::new();
+            for value in values {
+                if !seen.insert(value) {
+                    issues.push(LintIssue::DuplicateEnumValue(key.to_string(), value.clone()));
+                }
+            }
+        }
+        SchemaField::Array { items: Some(items), .. } => lint_enums(key, items, issues),
+        _ => {}
+    }
+}
+
+/// whether every pattern of a field and the fields it contains was compiled
+fn patterns_compiled(field: &SchemaField, schema: &CompiledSchema) -> bool {
+    match field {
+        SchemaField::String {
+            pattern: Some(pattern),
+            ..
+        } => schema.patterns().contains_key(pattern),
+        SchemaField::Array { items: Some(items), .. } => patterns_compiled(items, schema),
+        _ => true,
+    }
+}
+
+/// whether `key` can be used as a variable name in a POSIX shell
+pub fn is_shell_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
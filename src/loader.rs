@@ -0,0 +1,657 @@
+use crate::location::Location;
+use crate::schema::Schema;
+use crate::validation::ValidationError;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("failed to load .env file {0}: {1}")]
+    ReadError(PathBuf, std::io::Error),
+    
+    #[error("error parsing .env entry at {0}: {1}")]
+    ParseError(Location, String),
+}
+
+/// variables loaded from one or more .env files, with the origin of each key
+/// and any problems found while expanding `${VAR}` references
+#[derive(Debug, Default)]
+pub struct LoadedEnv {
+    pub vars: HashMap<String, String>,
+    pub origins: HashMap<String, Location>,
+    pub errors: Vec<ValidationError>,
+}
+
+impl LoadedEnv {
+    /// wrap variables that did not come from a file, such as the process environment
+    pub fn from_vars(vars: HashMap<String, String>) -> Self {
+        Self {
+            vars,
+            origins: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+    
+    pub fn origin(&self, key: &str) -> Option<&Location> {
+        self.origins.get(key)
+    }
+}
+
+/// load the variables defined in a .env file
+pub fn load_env_file<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>, LoadError> {
+    load_env_files(&[path]).map(|loaded| loaded.vars)
+}
+
+/// load several .env files in order, later files overriding earlier ones
+///
+/// the origin of every key is the position of the assignment that won.
+/// references are expanded once every file has been read, see `Resolver`.
+/// undefined and cyclic references and unset `${VAR:?message}` references
+/// are collected in `errors` and expand to an empty string.
+pub fn load_env_files<P: AsRef<Path>>(paths: &[P]) -> Result<LoadedEnv, LoadError> {
+    let mut definitions = Vec::new();
+    let mut loaded = LoadedEnv::default();
+    
+    for path in paths {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|err| LoadError::ReadError(path.to_path_buf(), err))?;
+        parse_into(&content, path, &mut definitions)?;
+    }
+    
+    let mut resolver = Resolver {
+        definitions: &definitions,
+        values: vec![None; definitions.len()],
+        resolving: Vec::new(),
+        errors: Vec::new(),
+    };
+    
+    for (index, definition) in definitions.iter().enumerate() {
+        let is_last = !definitions[index + 1..].iter().any(|later| later.key == definition.key);
+        if is_last {
+            let value = resolver.resolve(index);
+            loaded.vars.insert(definition.key.clone(), value);
+            loaded.origins.insert(definition.key.clone(), definition.location.clone());
+        }
+    }
+    
+    loaded.errors = resolver.errors;
+    Ok(loaded)
+}
+
+/// expand `--mode` into the usual cascade for each base file
+///
+/// for a base of `.env` and a mode of `production` this is `.env`,
+/// `.env.local`, `.env.production`, `.env.production.local`. files of the
+/// cascade that do not exist are skipped; if none of them exist the base file
+/// is kept so that loading reports it as missing.
+pub fn resolve_env_files(paths: &[PathBuf], mode: Option<&str>) -> Vec<PathBuf> {
+    let Some(mode) = mode else {
+        return paths.to_vec();
+    };
+    
+    let mut files = Vec::new();
+    for base in paths {
+        let base_name = base.as_os_str().to_string_lossy();
+        let cascade: Vec<PathBuf> = [
+            base_name.to_string(),
+            format!("{}.local", base_name),
+            format!("{}.{}", base_name, mode),
+            format!("{}.{}.local", base_name, mode),
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .collect();
+        
+        if cascade.is_empty() {
+            files.push(base.clone());
+        } else {
+            files.extend(cascade);
+        }
+    }
+    
+    files
+}
+
+/// collect the environment of the current process
+///
+/// variables whose name or value is not valid unicode are skipped.
+pub fn load_process_env() -> HashMap<String, String> {
+    env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+        .collect()
+}
+
+/// keep only the variables that belong to the application
+///
+/// a key is kept if it is declared in the schema, starts with one of
+/// `prefixes`, or is listed in `allow`. this keeps unrelated process variables
+/// such as `PATH` or `HOME` out of strict mode.
+pub fn scope_env(
+    env_vars: HashMap<String, String>,
+    schema: &Schema,
+    prefixes: &[String],
+    allow: &[String],
+) -> HashMap<String, String> {
+    env_vars
+        .into_iter()
+        .filter(|(key, _)| {
+            schema.contains_key(key)
+                || prefixes.iter().any(|prefix| key.starts_with(prefix.as_str()))
+                || allow.contains(key)
+        })
+        .collect()
+}
+
+/// a value as written, with references left unexpanded
+#[derive(Debug, Clone, Default)]
+struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Text(String),
+    Reference(Reference),
+}
+
+/// a `$NAME` or `${NAME}` reference, or a `${NAME}` with an operator such as
+/// `${NAME:-default}`
+#[derive(Debug, Clone)]
+struct Reference {
+    name: String,
+    fallback: Fallback,
+    /// whether an empty value counts as unset, as it does for the operators
+    /// written with a colon
+    empty_is_unset: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Fallback {
+    None,
+    /// `-` or `:-`, the text to use when the variable is unset
+    Default(Template),
+    /// `?` or `:?`, the message to report when the variable is unset
+    Required(String),
+    /// `+` or `:+`, the text to use when the variable is set
+    Alternative(Template),
+    /// an operator that is not supported, as written
+    Unsupported(String),
+}
+
+impl Template {
+    fn push(&mut self, c: char) {
+        match self.segments.last_mut() {
+            Some(Segment::Text(text)) => text.push(c),
+            _ => self.segments.push(Segment::Text(c.to_string())),
+        }
+    }
+    
+    fn push_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.push(c);
+        }
+    }
+    
+    fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+    
+    fn ends_with_blank(&self) -> bool {
+        matches!(self.segments.last(), Some(Segment::Text(text)) if text.ends_with([' ', '\t']))
+    }
+    
+    fn trim_end(mut self) -> Self {
+        if let Some(Segment::Text(text)) = self.segments.last_mut() {
+            let trimmed_len = text.trim_end().len();
+            text.truncate(trimmed_len);
+            if text.is_empty() {
+                self.segments.pop();
+            }
+        }
+        self
+    }
+}
+
+/// one assignment in one of the loaded files
+struct Definition {
+    key: String,
+    template: Template,
+    location: Location,
+}
+
+/// expands the references in every definition
+///
+/// a reference binds to the closest earlier assignment of that name. if there
+/// is none, it binds to the final assignment of the name in any of the files,
+/// so keys can refer to keys defined further down. a key referring to itself
+/// with no earlier assignment, and names that are not assigned anywhere, are
+/// read from the process environment.
+struct Resolver<'a> {
+    definitions: &'a [Definition],
+    values: Vec<Option<String>>,
+    resolving: Vec<usize>,
+    errors: Vec<ValidationError>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, index: usize) -> String {
+        if let Some(value) = &self.values[index] {
+            return value.clone();
+        }
+        
+        self.resolving.push(index);
+        let definitions = self.definitions;
+        let value = self.expand(&definitions[index].template, index);
+        self.resolving.pop();
+        
+        self.values[index] = Some(value.clone());
+        value
+    }
+    
+    fn expand(&mut self, template: &Template, index: usize) -> String {
+        let mut value = String::new();
+        
+        for segment in &template.segments {
+            match segment {
+                Segment::Text(text) => value.push_str(text),
+                Segment::Reference(reference) => {
+                    let key = &self.definitions[index].key;
+                    let resolved = self.lookup(&reference.name, index);
+                    let is_unset = match resolved.as_deref() {
+                        Some(resolved) => reference.empty_is_unset && resolved.is_empty(),
+                        None => true,
+                    };
+                    
+                    match &reference.fallback {
+                        Fallback::Default(default) if is_unset => {
+                            value.push_str(&self.expand(default, index));
+                        }
+                        Fallback::Required(message) if is_unset => {
+                            self.errors.push(ValidationError::UnsetReference(
+                                key.clone(),
+                                reference.name.clone(),
+                                message.clone(),
+                            ));
+                        }
+                        Fallback::Alternative(alternative) => {
+                            if !is_unset {
+                                value.push_str(&self.expand(alternative, index));
+                            }
+                        }
+                        Fallback::Unsupported(operator) => {
+                            self.errors.push(ValidationError::UnsupportedExpansion(
+                                key.clone(),
+                                reference.name.clone(),
+                                operator.clone(),
+                            ));
+                        }
+                        Fallback::None if resolved.is_none() => {
+                            self.errors.push(ValidationError::UndefinedReference(
+                                key.clone(),
+                                reference.name.clone(),
+                            ));
+                        }
+                        _ => value.push_str(&resolved.unwrap_or_default()),
+                    }
+                }
+            }
+        }
+        
+        value
+    }
+    
+    /// the value `name` has from the point of view of definition `index`
+    fn lookup(&mut self, name: &str, index: usize) -> Option<String> {
+        let definitions = self.definitions;
+        let binding = definitions[..index]
+            .iter()
+            .rposition(|definition| definition.key == name)
+            .or_else(|| {
+                if definitions[index].key == name {
+                    return None;
+                }
+                definitions.iter().rposition(|definition| definition.key == name)
+            });
+        
+        let Some(binding) = binding else {
+            return env::var(name).ok();
+        };
+        
+        if let Some(start) = self.resolving.iter().position(|&resolving| resolving == binding) {
+            let mut cycle: Vec<String> = self.resolving[start..]
+                .iter()
+                .map(|&resolving| definitions[resolving].key.clone())
+                .collect();
+            cycle.push(name.to_string());
+            self.errors.push(ValidationError::CyclicReference(
+                definitions[index].key.clone(),
+                cycle,
+            ));
+            return Some(String::new());
+        }
+        
+        Some(self.resolve(binding))
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    line_start: usize,
+    file: &'a Path,
+}
+
+fn parse_into(content: &str, file: &Path, definitions: &mut Vec<Definition>) -> Result<(), LoadError> {
+    let mut parser = Parser {
+        chars: content.chars().collect(),
+        pos: 0,
+        line: 1,
+        line_start: 0,
+        file,
+    };
+    
+    loop {
+        parser.skip_whitespace();
+        let Some(c) = parser.peek() else {
+            return Ok(());
+        };
+        
+        if c == '#' {
+            parser.skip_line();
+            continue;
+        }
+        
+        let mut start = parser.here();
+        let mut key = parser.read_key();
+        if key == "export" && parser.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            parser.skip_blanks();
+            start = parser.here();
+            key = parser.read_key();
+        }
+        
+        if !is_valid_key(&key) {
+            return Err(LoadError::ParseError(start, format!("invalid key '{}'", key)));
+        }
+        
+        parser.skip_blanks();
+        if parser.next() != Some('=') {
+            return Err(LoadError::ParseError(start, format!("expected '=' after {}", key)));
+        }
+        parser.skip_blanks();
+        
+        let template = parser.read_value()?;
+        definitions.push(Definition {
+            key,
+            template,
+            location: start,
+        });
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+    
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.line_start = self.pos;
+        }
+        Some(c)
+    }
+    
+    fn here(&self) -> Location {
+        Location::new(self.file, self.line, self.pos - self.line_start + 1)
+    }
+    
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+    
+    fn skip_blanks(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.next();
+        }
+    }
+    
+    fn skip_line(&mut self) {
+        while let Some(c) = self.next() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+    
+    fn read_key(&mut self) -> String {
+        let mut key = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '=' || c == '#' {
+                break;
+            }
+            key.push(c);
+            self.next();
+        }
+        key
+    }
+    
+    fn read_value(&mut self) -> Result<Template, LoadError> {
+        let start = self.here();
+        let value = match self.peek() {
+            Some('\'') => {
+                self.next();
+                let mut value = Template::default();
+                loop {
+                    match self.next() {
+                        Some('\'') => break,
+                        Some(c) => value.push(c),
+                        None => {
+                            let message = "unterminated single-quoted value".to_string();
+                            return Err(LoadError::ParseError(start.clone(), message));
+                        }
+                    }
+                }
+                value
+            }
+            Some('"') => {
+                self.next();
+                let mut value = Template::default();
+                loop {
+                    match self.next() {
+                        Some('"') => break,
+                        Some('\\') => match self.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some(c @ ('"' | '\\' | '$' | '\'')) => value.push(c),
+                            Some(c) => {
+                                value.push('\\');
+                                value.push(c);
+                            }
+                            None => {
+                                let message = "unterminated double-quoted value".to_string();
+                                return Err(LoadError::ParseError(start.clone(), message));
+                            }
+                        },
+                        Some('$') => self.read_reference(&mut value)?,
+                        Some(c) => value.push(c),
+                        None => {
+                            let message = "unterminated double-quoted value".to_string();
+                            return Err(LoadError::ParseError(start.clone(), message));
+                        }
+                    }
+                }
+                value
+            }
+            _ => {
+                let mut value = Template::default();
+                while let Some(c) = self.peek() {
+                    if c == '\n' || (c == '#' && (value.is_empty() || value.ends_with_blank())) {
+                        break;
+                    }
+                    self.next();
+                    if c == '$' {
+                        self.read_reference(&mut value)?;
+                    } else {
+                        value.push(c);
+                    }
+                }
+                return Ok(value.trim_end());
+            }
+        };
+        
+        self.skip_blanks();
+        match self.peek() {
+            None | Some('\n') | Some('\r') => {}
+            Some('#') => self.skip_line(),
+            Some(c) => {
+                return Err(LoadError::ParseError(start, format!("unexpected '{}' after quoted value", c)));
+            }
+        }
+        
+        Ok(value)
+    }
+    
+    /// read a reference whose `$` was just consumed
+    ///
+    /// a `$` that does not start a reference is kept as text, as is an
+    /// unterminated `${NAME`.
+    fn read_reference(&mut self, value: &mut Template) -> Result<(), LoadError> {
+        let start = self.here();
+        let braced = self.peek() == Some('{');
+        if braced {
+            self.next();
+        }
+        
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_') {
+                break;
+            }
+            name.push(c);
+            self.next();
+        }
+        
+        let mut fallback = Fallback::None;
+        let mut empty_is_unset = false;
+        let has_operator = self.peek().is_some_and(|c| !(c == '}' || c == '"' || c.is_whitespace()));
+        if braced && !name.is_empty() && has_operator {
+            empty_is_unset = self.peek() == Some(':');
+            if empty_is_unset {
+                self.next();
+            }
+            fallback = match self.next() {
+                Some('-') => Fallback::Default(self.read_fallback(&start)?),
+                Some('?') => {
+                    let message = self.read_fallback(&start)?;
+                    let mut text = String::new();
+                    for segment in message.segments {
+                        if let Segment::Text(part) = segment {
+                            text.push_str(&part);
+                        }
+                    }
+                    Fallback::Required(text)
+                }
+                Some('+') => Fallback::Alternative(self.read_fallback(&start)?),
+                Some('}') => Fallback::Unsupported(":".to_string()),
+                Some(c) if c != '\n' => {
+                    self.read_fallback(&start)?;
+                    let colon = if empty_is_unset { ":" } else { "" };
+                    Fallback::Unsupported(format!("{}{}", colon, c))
+                }
+                _ => {
+                    let message = "unterminated ${...} reference".to_string();
+                    return Err(LoadError::ParseError(start, message));
+                }
+            };
+        } else if braced && self.peek() == Some('}') {
+            self.next();
+        } else if braced {
+            value.push_str("${");
+            value.push_str(&name);
+            return Ok(());
+        }
+        
+        if name.is_empty() {
+            value.push('$');
+            if braced {
+                value.push_str("{}");
+            }
+            return Ok(());
+        }
+        
+        value.segments.push(Segment::Reference(Reference {
+            name,
+            fallback,
+            empty_is_unset,
+        }));
+        Ok(())
+    }
+    
+    /// read the text after an operator such as `:-` up to the closing brace
+    ///
+    /// parts of the text can be quoted as in a shell: single quotes keep it
+    /// as written, double quotes still expand references and take `\"`,
+    /// `\\` and `\$` escapes.
+    fn read_fallback(&mut self, start: &Location) -> Result<Template, LoadError> {
+        let mut template = Template::default();
+        loop {
+            match self.next() {
+                Some('}') => return Ok(template),
+                Some('$') => self.read_reference(&mut template)?,
+                Some('\'') => loop {
+                    match self.next() {
+                        Some('\'') => break,
+                        Some(c) if c != '\n' => template.push(c),
+                        _ => return Err(unterminated_quote(start)),
+                    }
+                },
+                Some('"') => loop {
+                    match self.next() {
+                        Some('"') => break,
+                        Some('\\') => match self.next() {
+                            Some(c @ ('"' | '\\' | '$')) => template.push(c),
+                            Some(c) if c != '\n' => {
+                                template.push('\\');
+                                template.push(c);
+                            }
+                            _ => return Err(unterminated_quote(start)),
+                        },
+                        Some('$') => self.read_reference(&mut template)?,
+                        Some(c) if c != '\n' => template.push(c),
+                        _ => return Err(unterminated_quote(start)),
+                    }
+                },
+                Some(c) if c != '\n' => template.push(c),
+                _ => {
+                    let message = "unterminated ${...} reference".to_string();
+                    return Err(LoadError::ParseError(start.clone(), message));
+                }
+            }
+        }
+    }
+}
+
+fn unterminated_quote(start: &Location) -> LoadError {
+    let message = "unterminated quote in ${...} reference".to_string();
+    LoadError::ParseError(start.clone(), message)
+}
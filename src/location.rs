@@ -0,0 +1,35 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// a position in a .env or schema file, with 1-based line and column numbers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new<P: Into<PathBuf>>(file: P, line: usize, column: usize) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+/// where each env value and each schema entry was read from
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    pub env: HashMap<String, Location>,
+    pub schema: HashMap<String, Location>,
+}
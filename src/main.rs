@@ -1,52 +1,191 @@
-mod cli;
-mod output;
-mod schema;
-mod validation;
+use dotenvcrab::cli::Command;
+use dotenvcrab::loader::LoadedEnv;
+use dotenvcrab::location::SourceMap;
+use dotenvcrab::validation::ValidationResult;
+use dotenvcrab::schema::Schema;
+use dotenvcrab::{cli, generate, json_schema, loader, output, run, schema, validation};
 
 use clap::Parser;
-use std::collections::HashMap;
+use colored::Colorize;
+use std::fs;
 use std::process;
 
 fn main() {
 
     let args = cli::Cli::parse();
     
-    let schema = match schema::load_schema(&args.schema) {
-        Ok(schema) => schema,
+    if args.from_process_env && args.strict && args.prefix.is_empty() && args.allow.is_empty() {
+        eprintln!("--strict with --from-process-env requires --prefix or --allow");
+        process::exit(1);
+    }
+    
+    if args.generate {
+        let loaded = load_env(&args, &Schema::new());
+        let generated = generate::generate_schema(&loaded.vars);
+        
+        if let Err(err) = generate::write_schema(&generated, &args.schema, args.force) {
+            eprintln!("Failed to generate schema: {}", err);
+            process::exit(1);
+        }
+        
+        println!(
+            "✅ Generated {} with {} field(s)",
+            args.schema.display(),
+            generated.len()
+        );
+        return;
+    }
+    
+    if let Some(Command::LintSchema) = &args.command {
+        lint_schema(&args);
+    }
+    
+    let loaded_schema = match schema::load_schema_with_locations(&args.schema) {
+        Ok(loaded_schema) => loaded_schema,
         Err(err) => {
             eprintln!("Failed to load schema: {}", err);
             process::exit(1);
         }
     };
+    for warning in &loaded_schema.issues {
+        eprintln!("{} {}", "warning:".yellow().bold(), warning);
+    }
+    let compiled = loaded_schema.schema;
+    let schema = compiled.schema();
+    
+    if let Some(Command::GenerateExample { output, force }) = &args.command {
+        if let Err(err) = generate::write_example(schema, output, *force) {
+            eprintln!("Failed to generate example: {}", err);
+            process::exit(1);
+        }
+        
+        println!(
+            "✅ Generated {} with {} key(s)",
+            output.display(),
+            schema.len()
+        );
+        return;
+    }
+    
+    if let Some(Command::ResolveSchema) = &args.command {
+        match generate::schema_to_json(schema) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                eprintln!("Failed to serialize schema: {}", err);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    if let Some(Command::ExportJsonschema) = &args.command {
+        match serde_json::to_string_pretty(&json_schema::export(schema)) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                eprintln!("Failed to serialize schema: {}", err);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    
+    let loaded = load_env(&args, schema);
+    
+    let mut validation_result = validation::validate_compiled(&loaded.vars, &compiled, args.strict);
+    for error in loaded.errors {
+        validation_result.add_error(error);
+    }
+    let sources = SourceMap {
+        env: loaded.origins,
+        schema: loaded_schema.locations,
+    };
     
-    let env_vars = match dotenvy::from_path_iter(&args.env) {
-        Ok(vars) => {
+    if let Some(Command::Run { command }) = &args.command {
+        if !validation_result.is_valid {
+            print_report(&args, &validation_result, schema, &sources);
+            process::exit(1);
+        }
+        
+        let vars = run::normalize(&run::with_defaults(&loaded.vars, schema), schema);
+        match run::exec_command(&command[0], &command[1..], &vars) {
+            Ok(code) => process::exit(code),
+            Err(err) => {
+                eprintln!("Failed to run {}: {}", command[0], err);
+                process::exit(1);
+            }
+        }
+    }
+    
+    print_report(&args, &validation_result, schema, &sources);
+    
+    if !validation_result.is_valid {
+        process::exit(1);
+    }
+}
 
-            let mut env_map = HashMap::new();
-            for var_result in vars {
-                match var_result {
-                    Ok((key, value)) => {
-                        env_map.insert(key, value);
-                    },
-                    Err(err) => {
-                        eprintln!("error parsing .env entry: {}", err);
-                        process::exit(1);
-                    }
-                }
+fn print_report(args: &cli::Cli, result: &ValidationResult, schema: &Schema, sources: &SourceMap) {
+    if args.output.is_some() {
+        colored::control::set_override(false);
+    }
+    
+    let rendered = if args.json {
+        output::render_json_result(result, sources) + "\n"
+    } else {
+        output::render_result(result, args.format, schema, sources)
+    };
+    
+    match &args.output {
+        Some(path) => {
+            if let Err(err) = fs::write(path, rendered) {
+                eprintln!("Failed to write report to {}: {}", path.display(), err);
+                process::exit(1);
             }
-            env_map
         }
+        None => print!("{}", rendered),
+    }
+}
+
+fn lint_schema(args: &cli::Cli) -> ! {
+    let loaded = match schema::read_schema_with_locations(&args.schema) {
+        Ok(loaded) => loaded,
         Err(err) => {
-            eprintln!("failed to load .env file: {}", err);
+            eprintln!("Failed to load schema: {}", err);
             process::exit(1);
         }
     };
     
-    let validation_result = validation::validate_env(&env_vars, &schema, args.strict);
+    let issues = loaded.issues;
+    for issue in &issues {
+        let location = loaded
+            .locations
+            .get(issue.key())
+            .map_or_else(|| args.schema.display().to_string(), |location| location.to_string());
+        let severity = if issue.is_error() {
+            "error:".red().bold()
+        } else {
+            "warning:".yellow().bold()
+        };
+        println!("{}: {} {}", location, severity, issue);
+    }
     
-    output::print_result(&validation_result, args.json);
+    if issues.is_empty() {
+        println!("✅ No problems found in {}", args.schema.display());
+    }
+    process::exit(if issues.iter().any(|issue| issue.is_error()) { 1 } else { 0 });
+}
+
+fn load_env(args: &cli::Cli, schema: &Schema) -> LoadedEnv {
+    if args.from_process_env {
+        let env_vars = loader::load_process_env();
+        return LoadedEnv::from_vars(loader::scope_env(env_vars, schema, &args.prefix, &args.allow));
+    }
     
-    if !validation_result.is_valid {
-        process::exit(1);
+    let files = loader::resolve_env_files(&args.env, args.mode.as_deref());
+    match loader::load_env_files(&files) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
     }
 }
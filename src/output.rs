@@ -1,6 +1,67 @@
+use crate::location::{Location, SourceMap};
+use crate::schema::Schema;
 use crate::validation::{ValidationError, ValidationResult};
+use clap::ValueEnum;
 use colored::*;
 use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+
+/// version of the structured JSON format, bumped on incompatible changes
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF rules, one per kind of validation error, keyed by `ValidationError::code`
+pub const SARIF_RULES: &[(&str, &str)] = &[
+    ("missing_required", "A required variable is not set"),
+    ("invalid_type", "A value does not have the type declared in the schema"),
+    ("invalid_enum", "A value is not one of the allowed enum values"),
+    ("extra_field", "A variable is not declared in the schema"),
+    ("pattern_mismatch", "A value does not match the pattern declared in the schema"),
+    ("invalid_regex", "A pattern in the schema is not a valid regular expression"),
+    ("not_integer", "A number is required to be an integer"),
+    ("below_minimum", "A number is below its minimum"),
+    ("above_maximum", "A number is above its maximum"),
+    ("below_exclusive_minimum", "A number is not greater than its exclusive minimum"),
+    ("above_exclusive_maximum", "A number is not less than its exclusive maximum"),
+    ("not_multiple_of", "A number is not a multiple of the required step"),
+    ("too_short", "A string is shorter than its minimum length"),
+    ("too_long", "A string is longer than its maximum length"),
+    ("invalid_format", "A string does not match its declared format"),
+    ("invalid_url", "A value cannot be parsed as a URL"),
+    ("invalid_url_scheme", "A URL uses a scheme that is not allowed"),
+    ("missing_url_component", "A URL is missing a required host, port or path"),
+    ("url_credentials_not_allowed", "A URL contains credentials where they are not allowed"),
+    ("too_few_items", "A list has fewer items than its minItems"),
+    ("too_many_items", "A list has more items than its maxItems"),
+    ("duplicate_item", "A list that must be unique contains a repeated item"),
+    ("invalid_item", "An item in a list does not match the item schema"),
+    ("invalid_json", "A value cannot be parsed as JSON"),
+    ("json_schema_mismatch", "A JSON value does not match its embedded JSON Schema"),
+    ("too_small", "A duration or byte size is below its min"),
+    ("too_large", "A duration or byte size is above its max"),
+    ("required_if", "A variable is missing while its requiredIf condition holds"),
+    ("required_unless", "A variable is missing and its requiredUnless condition does not hold"),
+    ("dependent_required", "A variable is missing although a variable that depends on it is set"),
+    ("mutually_exclusive", "Two variables that exclude each other are both set"),
+    ("one_of_group_missing", "No variable in a oneOfGroup is set"),
+    ("one_of_group_conflict", "More than one variable in a oneOfGroup is set"),
+    ("invalid_expression", "A constraint expression cannot be parsed or evaluated"),
+    ("constraint_failed", "A constraint expression evaluated to false"),
+    ("undefined_reference", "A ${VAR} reference names a variable that is not defined"),
+    ("cyclic_reference", "${VAR} references form a cycle"),
+    ("unset_reference", "A ${VAR:?message} reference names a variable that is unset or empty"),
+    ("unsupported_expansion", "A ${VAR...} reference uses an operator other than -, ?, + or their : forms"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
+    Junit,
+}
 
 #[derive(Serialize)]
 struct JsonOutput {
@@ -8,72 +69,577 @@ struct JsonOutput {
     errors: Vec<String>,
 }
 
-pub fn print_result(result: &ValidationResult, json_output: bool) {
-    if json_output {
-        print_json_result(result);
-    } else {
-        print_colored_result(result);
+#[derive(Serialize)]
+struct StructuredOutput<'a> {
+    version: u32,
+    valid: bool,
+    errors: Vec<StructuredError<'a>>,
+}
+
+#[derive(Serialize)]
+struct StructuredError<'a> {
+    code: &'static str,
+    key: &'a str,
+    expected: Option<Value>,
+    actual: Option<&'a str>,
+    message: String,
+    location: Option<&'a Location>,
+}
+
+pub fn print_result(
+    result: &ValidationResult,
+    format: OutputFormat,
+    schema: &Schema,
+    sources: &SourceMap,
+) {
+    let rendered = render_result(result, format, schema, sources);
+    print!("{}", rendered);
+}
+
+pub fn render_result(
+    result: &ValidationResult,
+    format: OutputFormat,
+    schema: &Schema,
+    sources: &SourceMap,
+) -> String {
+    match format {
+        OutputFormat::Text => render_colored_result(result, sources),
+        OutputFormat::Json => render_structured_result(result, sources) + "\n",
+        OutputFormat::Sarif => render_sarif_result(result, sources) + "\n",
+        OutputFormat::Junit => render_junit_result(result, schema, sources),
     }
 }
 
-pub fn print_colored_result(result: &ValidationResult) {
+pub fn print_colored_result(result: &ValidationResult, sources: &SourceMap) {
+    print!("{}", render_colored_result(result, sources));
+}
+
+/// render the human-readable report, one line per error
+pub fn render_colored_result(result: &ValidationResult, sources: &SourceMap) -> String {
+    let mut out = String::new();
+    
     if result.is_valid {
-        println!("{}", "✅ All environment variables are valid!".green().bold());
+        out.push_str(&format!("{}\n", "✅ All environment variables are valid!".green().bold()));
     } else {
-        println!("{}", "❌ Invalid .env:".red().bold());
+        out.push_str(&format!("{}\n", "❌ Invalid .env:".red().bold()));
         
         for error in &result.errors {
-            match error {
-                ValidationError::MissingRequired(key) => {
-                    println!("- {}: {}", key.yellow(), "missing".red());
-                }
-                ValidationError::InvalidType(key, expected, got) => {
-                    println!(
-                        "- {}: expected {}, got {}",
-                        key.yellow(),
-                        expected.green(),
-                        got.red()
-                    );
-                }
-                ValidationError::InvalidEnum(key, values, got) => {
-                    println!(
-                        "- {}: expected one of {}, got {}",
-                        key.yellow(),
-                        format!("[{}]", values.join(", ")).green(),
-                        got.red()
-                    );
-                }
-                ValidationError::ExtraField(key) => {
-                    println!("- {}: {}", key.yellow(), "not in schema".red());
-                }
-                ValidationError::InvalidPattern(key, pattern) => {
-                    println!(
-                        "- {}: value does not match pattern {}",
-                        key.yellow(),
-                        pattern.green()
-                    );
-                },
-                ValidationError::InvalidRegexPattern(key, error) => {
-                    println!(
-                        "- {}: invalid regex pattern: {}",
-                        key.yellow(),
-                        error.red()
-                    );
+            let message = colored_message(error);
+            
+            match error.location(sources) {
+                Some(location) => {
+                    out.push_str(&format!("- {} {}\n", message, format!("({})", location).dimmed()));
                 }
+                None => out.push_str(&format!("- {}\n", message)),
             }
         }
     }
+    
+    out
+}
+
+fn colored_message(error: &ValidationError) -> String {
+    match error {
+        ValidationError::MissingRequired(key) => {
+            format!("{}: {}", key.yellow(), "missing".red())
+        }
+        ValidationError::InvalidType(key, expected, got) => {
+            format!(
+                "{}: expected {}, got {}",
+                key.yellow(),
+                expected.green(),
+                got.red()
+            )
+        }
+        ValidationError::InvalidEnum(key, values, got) => {
+            format!(
+                "{}: expected one of {}, got {}",
+                key.yellow(),
+                format!("[{}]", values.join(", ")).green(),
+                got.red()
+            )
+        }
+        ValidationError::ExtraField(key) => {
+            format!("{}: {}", key.yellow(), "not in schema".red())
+        }
+        ValidationError::InvalidPattern(key, pattern, _) => {
+            format!(
+                "{}: value does not match pattern {}",
+                key.yellow(),
+                pattern.green()
+            )
+        },
+        ValidationError::InvalidRegexPattern(key, error) => {
+            format!(
+                "{}: invalid regex pattern: {}",
+                key.yellow(),
+                error.red()
+            )
+        }
+        ValidationError::NotInteger(key, got) => {
+            format!(
+                "{}: expected {}, got {}",
+                key.yellow(),
+                "an integer".green(),
+                got.red()
+            )
+        }
+        ValidationError::BelowMinimum(key, min, got) => {
+            format!(
+                "{}: expected at least {}, got {}",
+                key.yellow(),
+                min.to_string().green(),
+                got.red()
+            )
+        }
+        ValidationError::AboveMaximum(key, max, got) => {
+            format!(
+                "{}: expected at most {}, got {}",
+                key.yellow(),
+                max.to_string().green(),
+                got.red()
+            )
+        }
+        ValidationError::BelowExclusiveMinimum(key, min, got) => {
+            format!(
+                "{}: expected greater than {}, got {}",
+                key.yellow(),
+                min.to_string().green(),
+                got.red()
+            )
+        }
+        ValidationError::AboveExclusiveMaximum(key, max, got) => {
+            format!(
+                "{}: expected less than {}, got {}",
+                key.yellow(),
+                max.to_string().green(),
+                got.red()
+            )
+        }
+        ValidationError::NotMultipleOf(key, step, got) => {
+            format!(
+                "{}: expected a multiple of {}, got {}",
+                key.yellow(),
+                step.to_string().green(),
+                got.red()
+            )
+        }
+        ValidationError::TooShort(key, min, got) => {
+            format!(
+                "{}: expected at least {} characters, got {}",
+                key.yellow(),
+                min.to_string().green(),
+                got.chars().count().to_string().red()
+            )
+        }
+        ValidationError::TooLong(key, max, got) => {
+            format!(
+                "{}: expected at most {} characters, got {}",
+                key.yellow(),
+                max.to_string().green(),
+                got.chars().count().to_string().red()
+            )
+        }
+        ValidationError::InvalidFormat(key, format, got) => {
+            format!(
+                "{}: expected a valid {}, got {}",
+                key.yellow(),
+                format.name().green(),
+                got.red()
+            )
+        }
+        ValidationError::InvalidUrl(key, _, error) => {
+            format!("{}: invalid URL: {}", key.yellow(), error.red())
+        }
+        ValidationError::InvalidUrlScheme(key, schemes, got) => {
+            format!(
+                "{}: expected URL scheme {}, got {}",
+                key.yellow(),
+                format!("[{}]", schemes.join(", ")).green(),
+                got.red()
+            )
+        }
+        ValidationError::MissingUrlComponent(key, component) => {
+            format!("{}: URL is missing a {}", key.yellow(), component.red())
+        }
+        ValidationError::UrlCredentialsNotAllowed(key) => {
+            format!("{}: {}", key.yellow(), "URL must not contain credentials".red())
+        }
+        ValidationError::TooFewItems(key, min, got) => {
+            format!(
+                "{}: expected at least {} items, got {}",
+                key.yellow(),
+                min.to_string().green(),
+                got.to_string().red()
+            )
+        }
+        ValidationError::TooManyItems(key, max, got) => {
+            format!(
+                "{}: expected at most {} items, got {}",
+                key.yellow(),
+                max.to_string().green(),
+                got.to_string().red()
+            )
+        }
+        ValidationError::DuplicateItem(key, index, item) => {
+            format!(
+                "{}: duplicate item {} at index {}",
+                key.yellow(),
+                item.red(),
+                index
+            )
+        }
+        ValidationError::InvalidItem(_, _, error) => colored_message(error),
+        ValidationError::InvalidJson(key, _, error) => {
+            format!("{}: invalid JSON: {}", key.yellow(), error.red())
+        }
+        ValidationError::JsonSchemaMismatch(key, pointer, message, _) => {
+            format!(
+                "{}: at {} {}",
+                key.yellow(),
+                format!("\"{}\"", pointer).green(),
+                message.red()
+            )
+        }
+        ValidationError::TooSmall(key, min, got) => {
+            format!("{}: expected at least {}, got {}", key.yellow(), min.green(), got.red())
+        }
+        ValidationError::TooLarge(key, max, got) => {
+            format!("{}: expected at most {}, got {}", key.yellow(), max.green(), got.red())
+        }
+        ValidationError::RequiredIf(key, condition) => {
+            format!("{}: {} when {}", key.yellow(), "missing".red(), condition.green())
+        }
+        ValidationError::RequiredUnless(key, condition) => {
+            format!("{}: {} unless {}", key.yellow(), "missing".red(), condition.green())
+        }
+        ValidationError::DependentRequired(key, trigger) => {
+            format!("{}: {} because {} is set", key.yellow(), "missing".red(), trigger.green())
+        }
+        ValidationError::MutuallyExclusive(key, other) => {
+            format!("{}: cannot be set together with {}", key.yellow(), other.red())
+        }
+        ValidationError::OneOfGroupMissing(key, group, members) => {
+            format!(
+                "{}: one of {} must be set (group {})",
+                key.yellow(),
+                format!("[{}]", members.join(", ")).green(),
+                group
+            )
+        }
+        ValidationError::OneOfGroupConflict(key, group, set) => {
+            format!(
+                "{}: only one of {} may be set (group {})",
+                key.yellow(),
+                format!("[{}]", set.join(", ")).red(),
+                group
+            )
+        }
+        ValidationError::InvalidExpression(key, expr, error) => {
+            format!("{}: invalid constraint {}: {}", key.yellow(), expr.green(), error.red())
+        }
+        ValidationError::ConstraintFailed(key, _, message) => {
+            format!("{}: {}", key.yellow(), message.red())
+        }
+        ValidationError::UndefinedReference(key, name) => {
+            format!("{}: references undefined variable {}", key.yellow(), name.red())
+        }
+        ValidationError::CyclicReference(key, cycle) => {
+            format!("{}: cyclic reference {}", key.yellow(), cycle.join(" -> ").red())
+        }
+        ValidationError::UnsetReference(key, name, message) => {
+            format!("{}: {} is not set: {}", key.yellow(), name.red(), message)
+        }
+        ValidationError::UnsupportedExpansion(key, name, operator) => {
+            format!(
+                "{}: unsupported expansion {} in reference to {}",
+                key.yellow(),
+                operator.red(),
+                name
+            )
+        }
+    }
 }
 
-pub fn print_json_result(result: &ValidationResult) {
+pub fn print_json_result(result: &ValidationResult, sources: &SourceMap) {
+    println!("{}", render_json_result(result, sources));
+}
+
+/// render the result as JSON with one message string per error
+pub fn render_json_result(result: &ValidationResult, sources: &SourceMap) -> String {
     let json_output = JsonOutput {
         valid: result.is_valid,
         errors: result
             .errors
             .iter()
-            .map(|e| e.to_string())
+            .map(|e| match e.location(sources) {
+                Some(location) => format!("{}: {}", location, e),
+                None => e.to_string(),
+            })
+            .collect(),
+    };
+    
+    serde_json::to_string_pretty(&json_output).unwrap()
+}
+
+pub fn print_structured_result(result: &ValidationResult, sources: &SourceMap) {
+    println!("{}", render_structured_result(result, sources));
+}
+
+/// render the result as versioned JSON with one object per error
+pub fn render_structured_result(result: &ValidationResult, sources: &SourceMap) -> String {
+    let output = StructuredOutput {
+        version: JSON_FORMAT_VERSION,
+        valid: result.is_valid,
+        errors: result
+            .errors
+            .iter()
+            .map(|error| {
+                let (expected, actual) = expected_and_actual(error);
+                StructuredError {
+                    code: error.code(),
+                    key: error.key(),
+                    expected,
+                    actual,
+                    message: error.to_string(),
+                    location: error.location(sources),
+                }
+            })
             .collect(),
     };
     
-    println!("{}", serde_json::to_string_pretty(&json_output).unwrap());
+    serde_json::to_string_pretty(&output).unwrap()
+}
+
+fn expected_and_actual(error: &ValidationError) -> (Option<Value>, Option<&str>) {
+    match error {
+        ValidationError::MissingRequired(_) => (None, None),
+        ValidationError::InvalidType(_, expected, got) => (Some(json!(expected)), Some(got)),
+        ValidationError::InvalidEnum(_, values, got) => (Some(json!(values)), Some(got)),
+        ValidationError::ExtraField(_) => (None, None),
+        ValidationError::InvalidPattern(_, pattern, got) => (Some(json!(pattern)), Some(got)),
+        ValidationError::InvalidRegexPattern(..) => (None, None),
+        ValidationError::NotInteger(_, got) => (Some(json!("integer")), Some(got)),
+        ValidationError::BelowMinimum(_, min, got) => (Some(json!({ "minimum": min })), Some(got)),
+        ValidationError::AboveMaximum(_, max, got) => (Some(json!({ "maximum": max })), Some(got)),
+        ValidationError::BelowExclusiveMinimum(_, min, got) => {
+            (Some(json!({ "exclusiveMinimum": min })), Some(got))
+        }
+        ValidationError::AboveExclusiveMaximum(_, max, got) => {
+            (Some(json!({ "exclusiveMaximum": max })), Some(got))
+        }
+        ValidationError::NotMultipleOf(_, step, got) => {
+            (Some(json!({ "multipleOf": step })), Some(got))
+        }
+        ValidationError::TooShort(_, min, got) => (Some(json!({ "minLength": min })), Some(got)),
+        ValidationError::TooLong(_, max, got) => (Some(json!({ "maxLength": max })), Some(got)),
+        ValidationError::InvalidFormat(_, format, got) => {
+            (Some(json!({ "format": format.name() })), Some(got))
+        }
+        ValidationError::InvalidUrl(_, got, _) => (Some(json!("url")), Some(got)),
+        ValidationError::InvalidUrlScheme(_, schemes, got) => {
+            (Some(json!({ "schemes": schemes })), Some(got))
+        }
+        ValidationError::MissingUrlComponent(_, component) => (Some(json!(component)), None),
+        ValidationError::UrlCredentialsNotAllowed(_) => (None, None),
+        ValidationError::TooFewItems(_, min, _) => (Some(json!({ "minItems": min })), None),
+        ValidationError::TooManyItems(_, max, _) => (Some(json!({ "maxItems": max })), None),
+        ValidationError::DuplicateItem(_, _, item) => (Some(json!("unique items")), Some(item)),
+        ValidationError::InvalidItem(_, _, error) => expected_and_actual(error),
+        ValidationError::InvalidJson(_, got, _) => (Some(json!("json")), Some(got)),
+        ValidationError::JsonSchemaMismatch(_, pointer, _, node) => {
+            (Some(json!({ "pointer": pointer })), Some(node))
+        }
+        ValidationError::TooSmall(_, min, got) => (Some(json!({ "min": min })), Some(got)),
+        ValidationError::TooLarge(_, max, got) => (Some(json!({ "max": max })), Some(got)),
+        ValidationError::RequiredIf(_, condition) => (Some(json!({ "requiredIf": condition })), None),
+        ValidationError::RequiredUnless(_, condition) => {
+            (Some(json!({ "requiredUnless": condition })), None)
+        }
+        ValidationError::DependentRequired(_, trigger) => {
+            (Some(json!({ "dependentOf": trigger })), None)
+        }
+        ValidationError::MutuallyExclusive(_, other) => {
+            (Some(json!({ "mutuallyExclusive": other })), None)
+        }
+        ValidationError::OneOfGroupMissing(_, _, members) => (Some(json!({ "oneOf": members })), None),
+        ValidationError::OneOfGroupConflict(_, _, set) => (Some(json!({ "oneOf": set })), None),
+        ValidationError::InvalidExpression(_, expr, _) => (Some(json!(expr)), None),
+        ValidationError::ConstraintFailed(_, expr, _) => (Some(json!(expr)), None),
+        ValidationError::UndefinedReference(_, name) => (Some(json!({ "reference": name })), None),
+        ValidationError::CyclicReference(_, cycle) => (Some(json!({ "cycle": cycle })), None),
+        ValidationError::UnsetReference(_, name, _) => (Some(json!({ "reference": name })), None),
+        ValidationError::UnsupportedExpansion(_, name, operator) => {
+            (Some(json!({ "reference": name })), Some(operator))
+        }
+    }
+}
+
+pub fn print_sarif_result(result: &ValidationResult, sources: &SourceMap) {
+    println!("{}", render_sarif_result(result, sources));
+}
+
+/// render the result as a SARIF 2.1.0 log for code scanning tools
+pub fn render_sarif_result(result: &ValidationResult, sources: &SourceMap) -> String {
+    let rules: Vec<Value> = SARIF_RULES
+        .iter()
+        .map(|(id, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+    
+    let results: Vec<Value> = result
+        .errors
+        .iter()
+        .map(|error| {
+            let mut sarif_result = json!({
+                "ruleId": error.code(),
+                "ruleIndex": SARIF_RULES.iter().position(|(id, _)| *id == error.code()),
+                "level": "error",
+                "message": { "text": error.to_string() },
+            });
+            
+            if let Some(location) = error.location(sources) {
+                sarif_result["locations"] = json!([{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": location.file.to_string_lossy().replace('\\', "/"),
+                        },
+                        "region": {
+                            "startLine": location.line,
+                            "startColumn": location.column,
+                        },
+                    },
+                }]);
+            }
+            
+            sarif_result
+        })
+        .collect();
+    
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "dotenvcrab",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    
+    serde_json::to_string_pretty(&log).unwrap()
+}
+
+pub fn print_junit_result(result: &ValidationResult, schema: &Schema, sources: &SourceMap) {
+    print!("{}", render_junit_result(result, schema, sources));
+}
+
+/// render the result as a JUnit XML report
+///
+/// every schema key is a testcase, as is every key reported as an extra field.
+/// each validation error becomes a failure of the testcase for its key.
+pub fn render_junit_result(result: &ValidationResult, schema: &Schema, sources: &SourceMap) -> String {
+    let keys: BTreeSet<&str> = schema
+        .keys()
+        .map(String::as_str)
+        .chain(result.errors.iter().map(|error| error.key()))
+        .collect();
+    
+    let mut testcases = String::new();
+    let mut failed = 0;
+    
+    for key in &keys {
+        let failures: Vec<&ValidationError> = result
+            .errors
+            .iter()
+            .filter(|error| error.key() == *key)
+            .collect();
+        
+        if failures.is_empty() {
+            testcases.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"dotenvcrab\"/>\n",
+                xml_escape(key)
+            ));
+            continue;
+        }
+        
+        failed += 1;
+        testcases.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"dotenvcrab\">\n",
+            xml_escape(key)
+        ));
+        
+        for error in failures {
+            let (expected, actual) = expected_and_actual(error);
+            let mut details = Vec::new();
+            if let Some(expected) = expected {
+                details.push(format!("expected: {}", display_value(&expected)));
+            }
+            if let Some(actual) = actual {
+                details.push(format!("actual: {}", actual));
+            }
+            if let Some(location) = error.location(sources) {
+                details.push(format!("location: {}", location));
+            }
+            
+            testcases.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                error.code(),
+                xml_escape(&error.to_string()),
+                xml_escape(&details.join("\n"))
+            ));
+        }
+        
+        testcases.push_str("    </testcase>\n");
+    }
+    
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <testsuites name=\"dotenvcrab\" tests=\"{total}\" failures=\"{failed}\">\n\
+         \x20 <testsuite name=\"env\" tests=\"{total}\" failures=\"{failed}\" errors=\"0\" skipped=\"0\">\n\
+         {testcases}\
+         \x20 </testsuite>\n\
+         </testsuites>\n",
+        total = keys.len(),
+    )
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(display_value).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(map) => map
+            .iter()
+            .map(|(name, value)| format!("{} {}", name, display_value(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
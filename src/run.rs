@@ -0,0 +1,60 @@
+use crate::schema::Schema;
+use std::collections::HashMap;
+use std::io;
+use std::process::Command;
+
+/// merge schema defaults into the loaded variables
+///
+/// keys present in `env_vars` are kept as-is; keys that are absent but have a
+/// default in the schema are added with that default.
+pub fn with_defaults(env_vars: &HashMap<String, String>, schema: &Schema) -> HashMap<String, String> {
+    let mut resolved = env_vars.clone();
+    
+    for (key, field) in schema {
+        if !resolved.contains_key(key)
+            && let Some(default) = field.get_default()
+        {
+            resolved.insert(key.clone(), default);
+        }
+    }
+    
+    resolved
+}
+
+/// rewrite values into the form their schema field exports them in
+///
+/// see `SchemaField::normalize`; keys without a schema field are kept as-is.
+pub fn normalize(env_vars: &HashMap<String, String>, schema: &Schema) -> HashMap<String, String> {
+    env_vars
+        .iter()
+        .map(|(key, value)| match schema.get(key) {
+            Some(field) => (key.clone(), field.normalize(value)),
+            None => (key.clone(), value.clone()),
+        })
+        .collect()
+}
+
+/// run `program` with `args`, adding `vars` on top of the inherited environment
+///
+/// on unix the current process is replaced, so this only returns if the exec
+/// itself failed. elsewhere the child is spawned and its exit code is returned.
+pub fn exec_command(
+    program: &str,
+    args: &[String],
+    vars: &HashMap<String, String>,
+) -> io::Result<i32> {
+    let mut command = Command::new(program);
+    command.args(args).envs(vars);
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(command.exec())
+    }
+    
+    #[cfg(not(unix))]
+    {
+        let status = command.status()?;
+        Ok(status.code().unwrap_or(1))
+    }
+}
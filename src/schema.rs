@@ -1,8 +1,17 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use crate::document::{
+    locate_document_keys, locate_key_after, locate_property_keys, parse_document, DocumentError,
+};
+use crate::formats::{parse_byte_size, parse_duration};
+use crate::json_schema;
+use crate::compiled::CompiledSchema;
+use crate::lint::{lint, LintIssue};
+use crate::location::Location;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,8 +19,228 @@ pub enum SchemaError {
     #[error("failed to read schema file: {0}")]
     FileReadError(#[from] std::io::Error),
     
-    #[error("failed to parse schema JSON: {0}")]
-    ParseError(#[from] serde_json::Error),
+    #[error("failed to parse schema at {0}: {1}")]
+    ParseError(Location, String),
+    
+    #[error("schema {0} must be an object of fields")]
+    NotAnObject(PathBuf),
+    
+    #[error("invalid field {1} at {0}: {2}")]
+    InvalidField(Location, String, String),
+    
+    #[error("failed to load {0}: {1}")]
+    IncludeError(PathBuf, Box<SchemaError>),
+    
+    #[error("cyclic schema include: {}", display_chain(.0))]
+    CyclicInclude(Vec<PathBuf>),
+    
+    #[error("$extends in {0} must be a path or a list of paths")]
+    InvalidExtends(PathBuf),
+    
+    #[error("cannot resolve $ref {1} for {0}: {2}")]
+    InvalidRef(String, String, String),
+    
+    #[error("JSON Schema {0} uses keywords that cannot be mapped to schema fields: {}", .1.join(", "))]
+    UnmappedKeywords(PathBuf, Vec<String>),
+    
+    #[error("unknown key {2} in field {1} at {0}{}", display_suggestion(.3))]
+    UnknownKey(Location, String, String, Option<String>),
+    
+    #[error("invalid schema {0}: {}", display_issues(.1))]
+    LintErrors(PathBuf, Vec<LintIssue>),
+}
+
+impl From<DocumentError> for SchemaError {
+    fn from(err: DocumentError) -> Self {
+        SchemaError::ParseError(err.0, err.1)
+    }
+}
+
+fn display_suggestion(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean {}?", suggestion),
+        None => String::new(),
+    }
+}
+
+fn display_issues(issues: &[LintIssue]) -> String {
+    let messages: Vec<String> = issues.iter().map(LintIssue::to_string).collect();
+    messages.join("; ")
+}
+
+fn display_chain(paths: &[PathBuf]) -> String {
+    let names: Vec<String> = paths.iter().map(|path| path.display().to_string()).collect();
+    names.join(" -> ")
+}
+
+/// built-in formats that a string field can be checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StringFormat {
+    Url,
+    Email,
+    Hostname,
+    Ipv4,
+    Ipv6,
+    Uuid,
+    Port,
+    Duration,
+    DateTime,
+}
+
+impl StringFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StringFormat::Url => "url",
+            StringFormat::Email => "email",
+            StringFormat::Hostname => "hostname",
+            StringFormat::Ipv4 => "ipv4",
+            StringFormat::Ipv6 => "ipv6",
+            StringFormat::Uuid => "uuid",
+            StringFormat::Port => "port",
+            StringFormat::Duration => "duration",
+            StringFormat::DateTime => "date-time",
+        }
+    }
+}
+
+impl fmt::Display for StringFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// units a duration field can be exported in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DurationUnit {
+    #[serde(rename = "ns")]
+    Nanoseconds,
+    #[serde(rename = "us")]
+    Microseconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "m")]
+    Minutes,
+    #[serde(rename = "h")]
+    Hours,
+    #[serde(rename = "d")]
+    Days,
+}
+
+impl DurationUnit {
+    /// the length of one unit in nanoseconds
+    pub fn nanos(&self) -> u128 {
+        match self {
+            DurationUnit::Nanoseconds => 1,
+            DurationUnit::Microseconds => 1_000,
+            DurationUnit::Milliseconds => 1_000_000,
+            DurationUnit::Seconds => 1_000_000_000,
+            DurationUnit::Minutes => 60 * 1_000_000_000,
+            DurationUnit::Hours => 3600 * 1_000_000_000,
+            DurationUnit::Days => 86400 * 1_000_000_000,
+        }
+    }
+}
+
+/// units a byte-size field can be exported in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ByteUnit {
+    B,
+    KB,
+    MB,
+    GB,
+    TB,
+    KiB,
+    MiB,
+    GiB,
+    TiB,
+}
+
+impl ByteUnit {
+    /// the size of one unit in bytes
+    pub fn bytes(&self) -> u64 {
+        match self {
+            ByteUnit::B => 1,
+            ByteUnit::KB => 1000,
+            ByteUnit::MB => 1000u64.pow(2),
+            ByteUnit::GB => 1000u64.pow(3),
+            ByteUnit::TB => 1000u64.pow(4),
+            ByteUnit::KiB => 1 << 10,
+            ByteUnit::MiB => 1 << 20,
+            ByteUnit::GiB => 1 << 30,
+            ByteUnit::TiB => 1 << 40,
+        }
+    }
+}
+
+/// a condition on other variables, written either as a variable name that must
+/// be set or as a map of variables to the values they must have
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Condition {
+    Set(String),
+    Equals(BTreeMap<String, String>),
+}
+
+impl Condition {
+    /// whether the condition holds, using `lookup` to read other variables
+    pub fn holds<'a>(&self, lookup: impl Fn(&str) -> Option<&'a str>) -> bool {
+        match self {
+            Condition::Set(key) => lookup(key).is_some(),
+            Condition::Equals(expected) => expected
+                .iter()
+                .all(|(key, value)| lookup(key) == Some(value.as_str())),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Set(key) => write!(f, "{} is set", key),
+            Condition::Equals(expected) => {
+                let parts: Vec<String> = expected
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                f.write_str(&parts.join(" and "))
+            }
+        }
+    }
+}
+
+/// rules relating a field to other variables, shared by every field type
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FieldRules {
+    /// the field is required while this condition holds
+    #[serde(default, rename = "requiredIf", skip_serializing_if = "Option::is_none")]
+    pub required_if: Option<Condition>,
+    /// the field is required unless this condition holds
+    #[serde(default, rename = "requiredUnless", skip_serializing_if = "Option::is_none")]
+    pub required_unless: Option<Condition>,
+    /// variables that must be set whenever this field is set
+    #[serde(default, rename = "dependentRequired", skip_serializing_if = "Vec::is_empty")]
+    pub dependent_required: Vec<String>,
+    /// variables that must not be set together with this field
+    #[serde(default, rename = "mutuallyExclusive", skip_serializing_if = "Vec::is_empty")]
+    pub mutually_exclusive: Vec<String>,
+    /// exactly one field in each named group must be set
+    #[serde(default, rename = "oneOfGroup", skip_serializing_if = "Option::is_none")]
+    pub one_of_group: Option<String>,
+    /// expressions over this and other variables that must hold
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<Constraint>,
+}
+
+/// a cross-field expression such as `POOL_MIN <= POOL_MAX`, see `expr::Expr`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Constraint {
+    pub expr: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -21,32 +250,56 @@ pub enum SchemaField {
     String {
         #[serde(default)]
         required: bool,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         default: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         description: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         pattern: Option<String>,
+        #[serde(default, rename = "minLength", skip_serializing_if = "Option::is_none")]
+        min_length: Option<usize>,
+        #[serde(default, rename = "maxLength", skip_serializing_if = "Option::is_none")]
+        max_length: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<StringFormat>,
+        #[serde(flatten)]
+        rules: FieldRules,
     },
     
     #[serde(rename = "number")]
     Number {
         #[serde(default)]
         required: bool,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         default: Option<f64>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minimum: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        maximum: Option<f64>,
+        #[serde(default, rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
+        exclusive_minimum: Option<f64>,
+        #[serde(default, rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
+        exclusive_maximum: Option<f64>,
+        #[serde(default, rename = "multipleOf", skip_serializing_if = "Option::is_none")]
+        multiple_of: Option<f64>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        integer: bool,
+        #[serde(flatten)]
+        rules: FieldRules,
     },
     
     #[serde(rename = "boolean")]
     Boolean {
         #[serde(default)]
         required: bool,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         default: Option<bool>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        #[serde(flatten)]
+        rules: FieldRules,
     },
     
     #[serde(rename = "enum")]
@@ -54,20 +307,200 @@ pub enum SchemaField {
         #[serde(default)]
         required: bool,
         values: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(flatten)]
+        rules: FieldRules,
+    },
+    
+    #[serde(rename = "url")]
+    Url {
+        #[serde(default)]
+        required: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        schemes: Vec<String>,
+        #[serde(default, rename = "requireHost", skip_serializing_if = "std::ops::Not::not")]
+        require_host: bool,
+        #[serde(default, rename = "requirePort", skip_serializing_if = "std::ops::Not::not")]
+        require_port: bool,
+        #[serde(default, rename = "requirePath", skip_serializing_if = "std::ops::Not::not")]
+        require_path: bool,
+        #[serde(default = "default_true", rename = "allowCredentials", skip_serializing_if = "Clone::clone")]
+        allow_credentials: bool,
+        #[serde(flatten)]
+        rules: FieldRules,
+    },
+    
+    #[serde(rename = "array")]
+    Array {
+        #[serde(default)]
+        required: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default = "default_separator")]
+        separator: String,
+        #[serde(default, rename = "minItems", skip_serializing_if = "Option::is_none")]
+        min_items: Option<usize>,
+        #[serde(default, rename = "maxItems", skip_serializing_if = "Option::is_none")]
+        max_items: Option<usize>,
+        #[serde(default, rename = "uniqueItems", skip_serializing_if = "std::ops::Not::not")]
+        unique_items: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        items: Option<Box<SchemaField>>,
+        #[serde(flatten)]
+        rules: FieldRules,
+    },
+    
+    #[serde(rename = "json")]
+    Json {
         #[serde(default)]
+        required: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        schema: Option<serde_json::Value>,
+        #[serde(flatten)]
+        rules: FieldRules,
+    },
+    
+    #[serde(rename = "duration")]
+    Duration {
         #[serde(default)]
+        required: bool,
+        #[serde(default, deserialize_with = "duration_value", skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         description: Option<String>,
+        #[serde(default, deserialize_with = "duration_value", skip_serializing_if = "Option::is_none")]
+        min: Option<String>,
+        #[serde(default, deserialize_with = "duration_value", skip_serializing_if = "Option::is_none")]
+        max: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<DurationUnit>,
+        #[serde(flatten)]
+        rules: FieldRules,
     },
+    
+    #[serde(rename = "bytesize")]
+    ByteSize {
+        #[serde(default)]
+        required: bool,
+        #[serde(default, deserialize_with = "byte_size_value", skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, deserialize_with = "byte_size_value", skip_serializing_if = "Option::is_none")]
+        min: Option<String>,
+        #[serde(default, deserialize_with = "byte_size_value", skip_serializing_if = "Option::is_none")]
+        max: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<ByteUnit>,
+        #[serde(flatten)]
+        rules: FieldRules,
+    },
+}
+
+/// keys that every field type accepts
+const COMMON_KEYS: [&str; 10] = [
+    "type",
+    "required",
+    "default",
+    "description",
+    "requiredIf",
+    "requiredUnless",
+    "dependentRequired",
+    "mutuallyExclusive",
+    "oneOfGroup",
+    "constraints",
+];
+
+/// keys that an entry of `constraints` accepts
+const CONSTRAINT_KEYS: [&str; 2] = ["expr", "message"];
+
+/// divide in integers so that exact results print without rounding noise,
+/// falling back to a decimal only when there is a remainder
+fn divide(amount: u128, unit: u128) -> String {
+    if amount.is_multiple_of(unit) {
+        (amount / unit).to_string()
+    } else {
+        (amount as f64 / unit as f64).to_string()
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_separator() -> String {
+    ",".to_string()
+}
+
+/// accept a duration string such as `30s`, rejecting unparseable values at load time
+fn duration_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    match parse_duration(&value) {
+        Some(_) => Ok(Some(value)),
+        None => Err(serde::de::Error::custom(format!("invalid duration: {}", value))),
+    }
+}
+
+/// accept a byte size such as `512MiB`, rejecting unparseable values at load time
+fn byte_size_value<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    match parse_byte_size(&value) {
+        Some(_) => Ok(Some(value)),
+        None => Err(serde::de::Error::custom(format!("invalid byte size: {}", value))),
+    }
 }
 
 impl SchemaField {
+    /// the keys a field of the given `type` accepts besides the ones every
+    /// field accepts, or `None` if there is no such type
+    ///
+    /// serde cannot reject unknown keys in an internally tagged enum with
+    /// flattened fields, so this list has to follow the variants above.
+    pub fn type_keys(type_name: &str) -> Option<&'static [&'static str]> {
+        match type_name {
+            "string" => Some(&["pattern", "minLength", "maxLength", "format"]),
+            "number" => Some(&[
+                "minimum",
+                "maximum",
+                "exclusiveMinimum",
+                "exclusiveMaximum",
+                "multipleOf",
+                "integer",
+            ]),
+            "boolean" => Some(&[]),
+            "enum" => Some(&["values"]),
+            "url" => Some(&["schemes", "requireHost", "requirePort", "requirePath", "allowCredentials"]),
+            "array" => Some(&["separator", "minItems", "maxItems", "uniqueItems", "items"]),
+            "json" => Some(&["schema"]),
+            "duration" | "bytesize" => Some(&["min", "max", "unit"]),
+            _ => None,
+        }
+    }
+    
     pub fn is_required(&self) -> bool {
         match self {
             SchemaField::String { required, .. } => *required,
             SchemaField::Number { required, .. } => *required,
             SchemaField::Boolean { required, .. } => *required,
             SchemaField::Enum { required, .. } => *required,
+            SchemaField::Url { required, .. } => *required,
+            SchemaField::Array { required, .. } => *required,
+            SchemaField::Json { required, .. } => *required,
+            SchemaField::Duration { required, .. } => *required,
+            SchemaField::ByteSize { required, .. } => *required,
         }
     }
     
@@ -77,15 +510,297 @@ impl SchemaField {
             SchemaField::Number { default, .. } => default.map(|n| n.to_string()),
             SchemaField::Boolean { default, .. } => default.map(|b| b.to_string()),
             SchemaField::Enum { default, .. } => default.clone(),
+            SchemaField::Url { default, .. } => default.clone(),
+            SchemaField::Array { default, .. } => default.clone(),
+            SchemaField::Json { default, .. } => default.clone(),
+            SchemaField::Duration { default, .. } => default.clone(),
+            SchemaField::ByteSize { default, .. } => default.clone(),
+        }
+    }
+    
+    pub fn rules(&self) -> &FieldRules {
+        match self {
+            SchemaField::String { rules, .. }
+            | SchemaField::Number { rules, .. }
+            | SchemaField::Boolean { rules, .. }
+            | SchemaField::Enum { rules, .. }
+            | SchemaField::Url { rules, .. }
+            | SchemaField::Array { rules, .. }
+            | SchemaField::Json { rules, .. }
+            | SchemaField::Duration { rules, .. }
+            | SchemaField::ByteSize { rules, .. } => rules,
+        }
+    }
+    
+    /// rewrite a value for export to a child process
+    ///
+    /// duration and byte-size fields with a `unit` are exported as a plain
+    /// number in that unit; every other value is returned unchanged.
+    pub fn normalize(&self, value: &str) -> String {
+        match self {
+            SchemaField::Duration { unit: Some(unit), .. } => match parse_duration(value) {
+                Some(duration) => divide(duration.as_nanos(), unit.nanos()),
+                None => value.to_string(),
+            },
+            SchemaField::ByteSize { unit: Some(unit), .. } => match parse_byte_size(value) {
+                Some(bytes) => divide(u128::from(bytes), u128::from(unit.bytes())),
+                None => value.to_string(),
+            },
+            _ => value.to_string(),
+        }
+    }
+    
+    pub fn description(&self) -> Option<&String> {
+        match self {
+            SchemaField::String { description, .. } => description.as_ref(),
+            SchemaField::Number { description, .. } => description.as_ref(),
+            SchemaField::Boolean { description, .. } => description.as_ref(),
+            SchemaField::Enum { description, .. } => description.as_ref(),
+            SchemaField::Url { description, .. } => description.as_ref(),
+            SchemaField::Array { description, .. } => description.as_ref(),
+            SchemaField::Json { description, .. } => description.as_ref(),
+            SchemaField::Duration { description, .. } => description.as_ref(),
+            SchemaField::ByteSize { description, .. } => description.as_ref(),
         }
     }
 }
 
 pub type Schema = HashMap<String, SchemaField>;
 
+/// a compiled schema together with the location of each key in its file
+#[derive(Debug, Default)]
+pub struct LoadedSchema {
+    pub schema: CompiledSchema,
+    pub locations: HashMap<String, Location>,
+    /// issues found by `lint::lint`; once the schema is loaded with
+    /// `load_schema_with_locations` only warnings are left
+    pub issues: Vec<LintIssue>,
+}
+
 pub fn load_schema<P: AsRef<Path>>(path: P) -> Result<Schema, SchemaError> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let schema = serde_json::from_reader(reader)?;
-    Ok(schema)
+    load_schema_with_locations(path).map(|loaded| loaded.schema.into_schema())
+}
+
+/// load, compile and lint a schema, rejecting it if there are lint errors
+/// such as a pattern that does not compile
+///
+/// the remaining issues are warnings and are kept in `LoadedSchema::issues`.
+pub fn load_schema_with_locations<P: AsRef<Path>>(path: P) -> Result<LoadedSchema, SchemaError> {
+    let mut loaded = read_schema_with_locations(path.as_ref())?;
+    
+    let (errors, warnings) = loaded.issues.into_iter().partition::<Vec<_>, _>(LintIssue::is_error);
+    if !errors.is_empty() {
+        return Err(SchemaError::LintErrors(path.as_ref().to_path_buf(), errors));
+    }
+    
+    loaded.issues = warnings;
+    Ok(loaded)
+}
+
+/// load a schema, resolve the `$extends` and `$ref` entries it uses, and
+/// compile and lint it, returning every lint issue without rejecting any
+///
+/// `$extends` is a path or list of paths, relative to the schema, whose fields
+/// are pulled in first; later entries override earlier ones and the schema's
+/// own fields override them all. a field of the form
+/// `{ "$ref": "shared.schema.json#/DATABASE_URL" }` copies a field from
+/// another file, and any other keys next to `$ref` override the copied ones.
+pub fn read_schema_with_locations<P: AsRef<Path>>(path: P) -> Result<LoadedSchema, SchemaError> {
+    let document = resolve_file(path.as_ref(), &mut Vec::new())?;
+    
+    let mut schema = Schema::new();
+    for (key, value) in document.fields {
+        let location = document
+            .locations
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| Location::new(path.as_ref(), 1, 1));
+        
+        if let Some((unknown, suggestion)) = find_unknown_key(&value) {
+            let location = fs::read_to_string(&location.file)
+                .ok()
+                .and_then(|content| locate_key_after(&content, &location, &unknown))
+                .unwrap_or(location);
+            return Err(SchemaError::UnknownKey(location, key, unknown, suggestion));
+        }
+        
+        let field = serde_json::from_value(value)
+            .map_err(|err| SchemaError::InvalidField(location, key.clone(), err.to_string()))?;
+        schema.insert(key, field);
+    }
+    
+    let (schema, issues) = lint(schema);
+    Ok(LoadedSchema {
+        schema,
+        locations: document.locations,
+        issues,
+    })
+}
+
+/// find a key in a raw field, in one of its `constraints`, or in the field of
+/// its `items`, that is not accepted there, along with the accepted key it is
+/// most likely a typo of
+///
+/// fields without a known `type` are left for serde to report.
+fn find_unknown_key(field: &Value) -> Option<(String, Option<String>)> {
+    let object = field.as_object()?;
+    let type_keys = SchemaField::type_keys(object.get("type")?.as_str()?)?;
+    let accepted: Vec<&str> = COMMON_KEYS.iter().chain(type_keys).copied().collect();
+    
+    for key in object.keys() {
+        if !accepted.contains(&key.as_str()) {
+            return Some((key.clone(), closest_key(key, &accepted)));
+        }
+    }
+    
+    let constraints = object.get("constraints").and_then(Value::as_array);
+    for constraint in constraints.into_iter().flatten().filter_map(Value::as_object) {
+        for key in constraint.keys() {
+            if !CONSTRAINT_KEYS.contains(&key.as_str()) {
+                return Some((key.clone(), closest_key(key, &CONSTRAINT_KEYS)));
+            }
+        }
+    }
+    object.get("items").and_then(find_unknown_key)
+}
+
+/// the candidate closest to `key` by edit distance, if it is close enough to
+/// be a typo
+fn closest_key(key: &str, candidates: &[&str]) -> Option<String> {
+    let max_distance = (key.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(&key.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// the Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    
+    previous[b.len()]
+}
+
+/// the raw fields of a schema file once its includes are resolved
+#[derive(Default)]
+struct ResolvedDocument {
+    fields: Map<String, Value>,
+    locations: HashMap<String, Location>,
+}
+
+/// read a schema file and merge in the files it extends or references
+///
+/// `stack` holds the files currently being resolved, to detect cycles.
+fn resolve_file(path: &Path, stack: &mut Vec<PathBuf>) -> Result<ResolvedDocument, SchemaError> {
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(start) = stack.iter().position(|included| *included == canonical) {
+        let mut chain = stack[start..].to_vec();
+        chain.push(canonical);
+        return Err(SchemaError::CyclicInclude(chain));
+    }
+    
+    let content = fs::read_to_string(path)?;
+    let Value::Object(mut object) = parse_document(&content, path)? else {
+        return Err(SchemaError::NotAnObject(path.to_path_buf()));
+    };
+    let locations = if json_schema::is_json_schema(&object) {
+        object = json_schema::translate(&object)
+            .map_err(|keywords| SchemaError::UnmappedKeywords(path.to_path_buf(), keywords))?;
+        locate_property_keys(&content, path, object.keys())
+    } else {
+        locate_document_keys(&content, path)
+    };
+    let base = path.parent().unwrap_or(Path::new(""));
+    
+    stack.push(canonical);
+    let mut resolved = ResolvedDocument::default();
+    
+    if let Some(extends) = object.remove("$extends") {
+        let includes = match extends {
+            Value::String(include) => vec![include],
+            Value::Array(includes) => includes
+                .into_iter()
+                .map(|include| match include {
+                    Value::String(include) => Ok(include),
+                    _ => Err(SchemaError::InvalidExtends(path.to_path_buf())),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => return Err(SchemaError::InvalidExtends(path.to_path_buf())),
+        };
+        
+        for include in includes {
+            let fragment = resolve_include(&base.join(include), stack)?;
+            resolved.fields.extend(fragment.fields);
+            resolved.locations.extend(fragment.locations);
+        }
+    }
+    
+    for (key, value) in object {
+        let value = match value.get("$ref") {
+            Some(reference) => resolve_ref(&key, reference, &value, base, stack)?,
+            None => value,
+        };
+        if let Some(location) = locations.get(&key) {
+            resolved.locations.insert(key.clone(), location.clone());
+        }
+        resolved.fields.insert(key, value);
+    }
+    
+    stack.pop();
+    Ok(resolved)
+}
+
+/// resolve another schema file, naming it in any error except a cycle
+fn resolve_include(path: &Path, stack: &mut Vec<PathBuf>) -> Result<ResolvedDocument, SchemaError> {
+    resolve_file(path, stack).map_err(|err| match err {
+        SchemaError::CyclicInclude(..) => err,
+        err => SchemaError::IncludeError(path.to_path_buf(), Box::new(err)),
+    })
+}
+
+/// replace a `$ref` field with the field it points at, keeping local overrides
+fn resolve_ref(
+    key: &str,
+    reference: &Value,
+    field: &Value,
+    base: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Value, SchemaError> {
+    let invalid = |reason: &str| {
+        SchemaError::InvalidRef(key.to_string(), reference.to_string(), reason.to_string())
+    };
+    
+    let Some((file, pointer)) = reference.as_str().and_then(|reference| reference.split_once('#')) else {
+        return Err(invalid("expected a string of the form file#/KEY"));
+    };
+    if file.is_empty() {
+        return Err(invalid("references must name a schema file"));
+    }
+    
+    let fragment = resolve_include(&base.join(file), stack)?;
+    let Some(Value::Object(target)) = Value::Object(fragment.fields).pointer(pointer).cloned() else {
+        return Err(invalid("no field at that path"));
+    };
+    
+    let mut merged = target;
+    if let Value::Object(overrides) = field {
+        for (name, value) in overrides {
+            if name != "$ref" {
+                merged.insert(name.clone(), value.clone());
+            }
+        }
+    }
+    Ok(Value::Object(merged))
 }
@@ -1,7 +1,13 @@
-use crate::schema::{Schema, SchemaField};
-use std::collections::{HashMap, HashSet};
+use crate::compiled::CompiledSchema;
+use crate::location::{Location, SourceMap};
+use crate::formats::{matches_format, parse_byte_size, parse_duration};
+use crate::expr::{Expr, ExprError, Value};
+use crate::json_schema;
+use crate::schema::{Constraint, Schema, SchemaField, StringFormat};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use thiserror::Error;
 use regex::Regex;
+use url::Url;
 
 #[derive(Debug, Error)]
 pub enum ValidationError {
@@ -18,10 +24,224 @@ pub enum ValidationError {
     ExtraField(String),
     
     #[error("value for {0} does not match pattern: {1}")]
-    InvalidPattern(String, String),
+    InvalidPattern(String, String, String),
     
     #[error("invalid regex pattern for {0}: {1}")]
     InvalidRegexPattern(String, String),
+    
+    #[error("value for {0} must be an integer, got {1}")]
+    NotInteger(String, String),
+    
+    #[error("value for {0} must be at least {1}, got {2}")]
+    BelowMinimum(String, f64, String),
+    
+    #[error("value for {0} must be at most {1}, got {2}")]
+    AboveMaximum(String, f64, String),
+    
+    #[error("value for {0} must be greater than {1}, got {2}")]
+    BelowExclusiveMinimum(String, f64, String),
+    
+    #[error("value for {0} must be less than {1}, got {2}")]
+    AboveExclusiveMaximum(String, f64, String),
+    
+    #[error("value for {0} must be a multiple of {1}, got {2}")]
+    NotMultipleOf(String, f64, String),
+    
+    #[error("value for {0} must be at least {1} characters long, got {len}", len = .2.chars().count())]
+    TooShort(String, usize, String),
+    
+    #[error("value for {0} must be at most {1} characters long, got {len}", len = .2.chars().count())]
+    TooLong(String, usize, String),
+    
+    #[error("invalid {1} for {0}: {2}")]
+    InvalidFormat(String, StringFormat, String),
+    
+    #[error("value for {0} is not a valid URL: {2}")]
+    InvalidUrl(String, String, String),
+    
+    #[error("invalid URL scheme for {0}: expected one of {1:?}, got {2}")]
+    InvalidUrlScheme(String, Vec<String>, String),
+    
+    #[error("URL for {0} is missing a {1}")]
+    MissingUrlComponent(String, String),
+    
+    #[error("URL for {0} must not contain credentials")]
+    UrlCredentialsNotAllowed(String),
+    
+    #[error("{0} must have at least {1} items, got {2}")]
+    TooFewItems(String, usize, usize),
+    
+    #[error("{0} must have at most {1} items, got {2}")]
+    TooManyItems(String, usize, usize),
+    
+    #[error("duplicate item in {0} at index {1}: {2}")]
+    DuplicateItem(String, usize, String),
+    
+    #[error("{2}")]
+    InvalidItem(String, usize, Box<ValidationError>),
+    
+    #[error("value for {0} is not valid JSON: {2}")]
+    InvalidJson(String, String, String),
+    
+    #[error("JSON for {0} does not match schema at \"{1}\": {2}")]
+    JsonSchemaMismatch(String, String, String, String),
+    
+    #[error("value for {0} must be at least {1}, got {2}")]
+    TooSmall(String, String, String),
+    
+    #[error("value for {0} must be at most {1}, got {2}")]
+    TooLarge(String, String, String),
+    
+    #[error("{0} is required when {1}")]
+    RequiredIf(String, String),
+    
+    #[error("{0} is required unless {1}")]
+    RequiredUnless(String, String),
+    
+    #[error("{0} is required because {1} is set")]
+    DependentRequired(String, String),
+    
+    #[error("{0} cannot be set together with {1}")]
+    MutuallyExclusive(String, String),
+    
+    #[error("one of {2:?} must be set (group {1})")]
+    OneOfGroupMissing(String, String, Vec<String>),
+    
+    #[error("only one of {2:?} may be set (group {1})")]
+    OneOfGroupConflict(String, String, Vec<String>),
+    
+    #[error("invalid constraint on {0} ({1}): {2}")]
+    InvalidExpression(String, String, String),
+    
+    #[error("constraint on {0} failed: {2}")]
+    ConstraintFailed(String, String, String),
+    
+    #[error("{0} references undefined variable {1}")]
+    UndefinedReference(String, String),
+    
+    #[error("{0} has a cyclic reference: {path}", path = .1.join(" -> "))]
+    CyclicReference(String, Vec<String>),
+    
+    #[error("{0} references {1}, which is not set: {2}")]
+    UnsetReference(String, String, String),
+    
+    #[error("{0} references {1} with unsupported expansion {2}")]
+    UnsupportedExpansion(String, String, String),
+}
+
+impl ValidationError {
+    /// the environment variable the error is about
+    pub fn key(&self) -> &str {
+        match self {
+            ValidationError::MissingRequired(key)
+            | ValidationError::InvalidType(key, ..)
+            | ValidationError::InvalidEnum(key, ..)
+            | ValidationError::ExtraField(key)
+            | ValidationError::InvalidPattern(key, ..)
+            | ValidationError::InvalidRegexPattern(key, _)
+            | ValidationError::NotInteger(key, _)
+            | ValidationError::BelowMinimum(key, ..)
+            | ValidationError::AboveMaximum(key, ..)
+            | ValidationError::BelowExclusiveMinimum(key, ..)
+            | ValidationError::AboveExclusiveMaximum(key, ..)
+            | ValidationError::NotMultipleOf(key, ..)
+            | ValidationError::TooShort(key, ..)
+            | ValidationError::TooLong(key, ..)
+            | ValidationError::InvalidFormat(key, ..)
+            | ValidationError::InvalidUrl(key, ..)
+            | ValidationError::InvalidUrlScheme(key, ..)
+            | ValidationError::MissingUrlComponent(key, _)
+            | ValidationError::UrlCredentialsNotAllowed(key)
+            | ValidationError::TooFewItems(key, ..)
+            | ValidationError::TooManyItems(key, ..)
+            | ValidationError::DuplicateItem(key, ..)
+            | ValidationError::InvalidItem(key, ..)
+            | ValidationError::InvalidJson(key, ..)
+            | ValidationError::JsonSchemaMismatch(key, ..)
+            | ValidationError::TooSmall(key, ..)
+            | ValidationError::TooLarge(key, ..)
+            | ValidationError::RequiredIf(key, _)
+            | ValidationError::RequiredUnless(key, _)
+            | ValidationError::DependentRequired(key, _)
+            | ValidationError::MutuallyExclusive(key, _)
+            | ValidationError::OneOfGroupMissing(key, ..)
+            | ValidationError::OneOfGroupConflict(key, ..)
+            | ValidationError::InvalidExpression(key, ..)
+            | ValidationError::ConstraintFailed(key, ..)
+            | ValidationError::UndefinedReference(key, _)
+            | ValidationError::CyclicReference(key, _)
+            | ValidationError::UnsetReference(key, ..)
+            | ValidationError::UnsupportedExpansion(key, ..) => key,
+        }
+    }
+    
+    /// a stable machine-readable identifier for the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::MissingRequired(_) => "missing_required",
+            ValidationError::InvalidType(..) => "invalid_type",
+            ValidationError::InvalidEnum(..) => "invalid_enum",
+            ValidationError::ExtraField(_) => "extra_field",
+            ValidationError::InvalidPattern(..) => "pattern_mismatch",
+            ValidationError::InvalidRegexPattern(..) => "invalid_regex",
+            ValidationError::NotInteger(..) => "not_integer",
+            ValidationError::BelowMinimum(..) => "below_minimum",
+            ValidationError::AboveMaximum(..) => "above_maximum",
+            ValidationError::BelowExclusiveMinimum(..) => "below_exclusive_minimum",
+            ValidationError::AboveExclusiveMaximum(..) => "above_exclusive_maximum",
+            ValidationError::NotMultipleOf(..) => "not_multiple_of",
+            ValidationError::TooShort(..) => "too_short",
+            ValidationError::TooLong(..) => "too_long",
+            ValidationError::InvalidFormat(..) => "invalid_format",
+            ValidationError::InvalidUrl(..) => "invalid_url",
+            ValidationError::InvalidUrlScheme(..) => "invalid_url_scheme",
+            ValidationError::MissingUrlComponent(..) => "missing_url_component",
+            ValidationError::UrlCredentialsNotAllowed(_) => "url_credentials_not_allowed",
+            ValidationError::TooFewItems(..) => "too_few_items",
+            ValidationError::TooManyItems(..) => "too_many_items",
+            ValidationError::DuplicateItem(..) => "duplicate_item",
+            ValidationError::InvalidItem(..) => "invalid_item",
+            ValidationError::InvalidJson(..) => "invalid_json",
+            ValidationError::JsonSchemaMismatch(..) => "json_schema_mismatch",
+            ValidationError::TooSmall(..) => "too_small",
+            ValidationError::TooLarge(..) => "too_large",
+            ValidationError::RequiredIf(..) => "required_if",
+            ValidationError::RequiredUnless(..) => "required_unless",
+            ValidationError::DependentRequired(..) => "dependent_required",
+            ValidationError::MutuallyExclusive(..) => "mutually_exclusive",
+            ValidationError::OneOfGroupMissing(..) => "one_of_group_missing",
+            ValidationError::OneOfGroupConflict(..) => "one_of_group_conflict",
+            ValidationError::InvalidExpression(..) => "invalid_expression",
+            ValidationError::ConstraintFailed(..) => "constraint_failed",
+            ValidationError::UndefinedReference(..) => "undefined_reference",
+            ValidationError::CyclicReference(..) => "cyclic_reference",
+            ValidationError::UnsetReference(..) => "unset_reference",
+            ValidationError::UnsupportedExpansion(..) => "unsupported_expansion",
+        }
+    }
+    
+    /// where the error should point: the schema entry for missing keys and
+    /// schema mistakes, the variable that triggered a missing dependency, and
+    /// the assignment in the .env file otherwise
+    pub fn location<'a>(&self, sources: &'a SourceMap) -> Option<&'a Location> {
+        match self {
+            ValidationError::MissingRequired(key)
+            | ValidationError::InvalidRegexPattern(key, _)
+            | ValidationError::InvalidExpression(key, ..)
+            | ValidationError::RequiredIf(key, _)
+            | ValidationError::RequiredUnless(key, _)
+            | ValidationError::OneOfGroupMissing(key, ..) => sources.schema.get(key),
+            ValidationError::DependentRequired(_, trigger) => sources.env.get(trigger),
+            _ => sources.env.get(self.key()),
+        }
+    }
+}
+
+/// Literals accepted for boolean fields, compared case-insensitively.
+pub const BOOLEAN_LITERALS: [&str; 6] = ["true", "false", "1", "0", "yes", "no"];
+
+pub fn is_boolean_literal(value: &str) -> bool {
+    BOOLEAN_LITERALS.contains(&value.to_lowercase().as_str())
 }
 
 #[derive(Debug)]
@@ -30,6 +250,12 @@ pub struct ValidationResult {
     pub errors: Vec<ValidationError>,
 }
 
+impl Default for ValidationResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ValidationResult {
     pub fn new() -> Self {
         Self {
@@ -44,89 +270,592 @@ impl ValidationResult {
     }
 }
 
+/// validate `env_vars` against a schema that has not been compiled
+///
+/// patterns are compiled and constraint expressions parsed on every call, and
+/// one that does not compile is reported as `InvalidRegexPattern` or
+/// `InvalidExpression` each time it is checked. to validate more than once,
+/// compile the schema and use `validate_compiled`.
 pub fn validate_env(
     env_vars: &HashMap<String, String>,
     schema: &Schema,
     strict: bool,
+) -> ValidationResult {
+    validate_with_compiled(env_vars, schema, &HashMap::new(), &HashMap::new(), strict)
+}
+
+/// validate `env_vars` against a compiled schema, reusing its patterns and
+/// parsed constraint expressions
+pub fn validate_compiled(
+    env_vars: &HashMap<String, String>,
+    schema: &CompiledSchema,
+    strict: bool,
+) -> ValidationResult {
+    validate_with_compiled(
+        env_vars,
+        schema.schema(),
+        schema.patterns(),
+        schema.expressions(),
+        strict,
+    )
+}
+
+fn validate_with_compiled(
+    env_vars: &HashMap<String, String>,
+    schema: &Schema,
+    patterns: &HashMap<String, Regex>,
+    expressions: &HashMap<String, Expr>,
+    strict: bool,
 ) -> ValidationResult {
     let mut result = ValidationResult::new();
-    let mut processed_keys = HashSet::new();
+    let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
     
-    for (key, field) in schema {
-        processed_keys.insert(key.clone());
-        
+    for (key, field) in sorted {
         if let Some(value) = env_vars.get(key) {
-            match field {
-                SchemaField::String { pattern, .. } => {
-                    if let Some(pattern_str) = pattern {
-                        match Regex::new(pattern_str) {
-                            Ok(regex) => {
-                                if !regex.is_match(value) {
-                                    result.add_error(ValidationError::InvalidPattern(
-                                        key.clone(),
-                                        pattern_str.clone(),
-                                    ));
-                                }
-                            },
-                            Err(err) => {
-                                result.add_error(ValidationError::InvalidRegexPattern(
-                                    key.clone(),
-                                    err.to_string(),
-                                ));
-                            }
-                        }
-                    }
+            for error in check_value(key, value, field, patterns) {
+                result.add_error(error);
+            }
+        } else if field.is_required() && field.get_default().is_none() {
+            result.add_error(ValidationError::MissingRequired(key.clone()));
+        }
+    }
+    
+    for error in check_rules(env_vars, schema, expressions) {
+        result.add_error(error);
+    }
+    
+    if strict {
+        let mut extra: Vec<&String> = env_vars
+            .keys()
+            .filter(|key| !schema.contains_key(*key))
+            .collect();
+        extra.sort();
+        for key in extra {
+            result.add_error(ValidationError::ExtraField(key.clone()));
+        }
+    }
+    
+    result
+}
+
+/// check the rules that relate fields to each other
+///
+/// a variable counts as set when it is in `env_vars`; requirements are also
+/// satisfied by a schema default. fields are visited in sorted order so the
+/// errors come out in a stable order.
+pub fn validate_rules(env_vars: &HashMap<String, String>, schema: &Schema) -> Vec<ValidationError> {
+    check_rules(env_vars, schema, &HashMap::new())
+}
+
+/// check the rules between fields, taking parsed constraints from
+/// `expressions` and parsing any constraint that is not there
+fn check_rules(
+    env_vars: &HashMap<String, String>,
+    schema: &Schema,
+    expressions: &HashMap<String, Expr>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let sorted: BTreeMap<&String, &SchemaField> = schema.iter().collect();
+    
+    let defaults: HashMap<&str, String> = schema
+        .iter()
+        .filter_map(|(key, field)| field.get_default().map(|default| (key.as_str(), default)))
+        .collect();
+    let lookup = |key: &str| -> Option<&str> {
+        env_vars
+            .get(key)
+            .or_else(|| defaults.get(key))
+            .map(String::as_str)
+    };
+    let satisfied = |key: &str| lookup(key).is_some();
+    
+    let mut exclusive_pairs = HashSet::new();
+    let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    
+    for (key, field) in &sorted {
+        let rules = field.rules();
+        // required fields that are missing are already reported by the field pass
+        if !field.is_required() && !satisfied(key) {
+            if let Some(condition) = &rules.required_if
+                && condition.holds(lookup)
+            {
+                errors.push(ValidationError::RequiredIf(key.to_string(), condition.to_string()));
+            }
+            if let Some(condition) = &rules.required_unless
+                && !condition.holds(lookup)
+            {
+                errors.push(ValidationError::RequiredUnless(key.to_string(), condition.to_string()));
+            }
+        }
+        
+        if env_vars.contains_key(*key) {
+            for dependency in &rules.dependent_required {
+                if !satisfied(dependency) {
+                    errors.push(ValidationError::DependentRequired(
+                        dependency.clone(),
+                        key.to_string(),
+                    ));
+                }
+            }
+            for other in &rules.mutually_exclusive {
+                let pair = if *key < other {
+                    (key.as_str(), other.as_str())
+                } else {
+                    (other.as_str(), key.as_str())
+                };
+                if env_vars.contains_key(other) && exclusive_pairs.insert(pair) {
+                    errors.push(ValidationError::MutuallyExclusive(key.to_string(), other.clone()));
                 }
-                
-                SchemaField::Number { .. } => {
-                    if value.parse::<f64>().is_err() {
-                        result.add_error(ValidationError::InvalidType(
-                            key.clone(),
-                            "number".to_string(),
-                            value.clone(),
+            }
+        }
+        
+        if let Some(group) = &rules.one_of_group {
+            groups.entry(group).or_default().push(key);
+        }
+        
+        for constraint in &rules.constraints {
+            let expr = expressions.get(&constraint.expr);
+            if let Some(error) = check_constraint(key, constraint, expr, schema, &lookup) {
+                errors.push(error);
+            }
+        }
+    }
+    
+    // a default satisfies a group, but only values that are actually set can
+    // conflict, as with `mutuallyExclusive`; otherwise a member with a default
+    // could never be replaced by another member
+    for (group, members) in groups {
+        let set: Vec<String> = members
+            .iter()
+            .filter(|member| env_vars.contains_key(**member))
+            .map(|member| member.to_string())
+            .collect();
+        
+        match set.len() {
+            0 if members.iter().any(|member| satisfied(member)) => {}
+            0 => errors.push(ValidationError::OneOfGroupMissing(
+                members[0].to_string(),
+                group.to_string(),
+                members.iter().map(|member| member.to_string()).collect(),
+            )),
+            1 => {}
+            _ => errors.push(ValidationError::OneOfGroupConflict(
+                set[1].clone(),
+                group.to_string(),
+                set,
+            )),
+        }
+    }
+    
+    errors
+}
+
+/// check a single value against its schema field
+pub fn validate_value(key: &str, value: &str, field: &SchemaField) -> Vec<ValidationError> {
+    check_value(key, value, field, &HashMap::new())
+}
+
+/// check a single value against its field, using the regexes of a compiled
+/// schema
+pub fn validate_compiled_value(
+    key: &str,
+    value: &str,
+    field: &SchemaField,
+    schema: &CompiledSchema,
+) -> Vec<ValidationError> {
+    check_value(key, value, field, schema.patterns())
+}
+
+/// check a single value, taking compiled regexes from `patterns` and
+/// compiling any pattern that is not there
+fn check_value(
+    key: &str,
+    value: &str,
+    field: &SchemaField,
+    patterns: &HashMap<String, Regex>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    
+    match field {
+        SchemaField::String {
+            pattern,
+            min_length,
+            max_length,
+            format,
+            ..
+        } => {
+            let length = value.chars().count();
+            if let Some(min) = min_length
+                && length < *min
+            {
+                errors.push(ValidationError::TooShort(key.to_string(), *min, value.to_string()));
+            }
+            if let Some(max) = max_length
+                && length > *max
+            {
+                errors.push(ValidationError::TooLong(key.to_string(), *max, value.to_string()));
+            }
+            if let Some(format) = format
+                && !matches_format(*format, value)
+            {
+                errors.push(ValidationError::InvalidFormat(
+                    key.to_string(),
+                    *format,
+                    value.to_string(),
+                ));
+            }
+            
+            if let Some(pattern_str) = pattern {
+                let compiled;
+                let regex = match patterns.get(pattern_str) {
+                    Some(regex) => Ok(regex),
+                    None => {
+                        compiled = Regex::new(pattern_str);
+                        compiled.as_ref()
+                    }
+                };
+                match regex {
+                    Ok(regex) => {
+                        if !regex.is_match(value) {
+                            errors.push(ValidationError::InvalidPattern(
+                                key.to_string(),
+                                pattern_str.clone(),
+                                value.to_string(),
+                            ));
+                        }
+                    },
+                    Err(err) => {
+                        errors.push(ValidationError::InvalidRegexPattern(
+                            key.to_string(),
+                            err.to_string(),
                         ));
                     }
                 }
-                
-                SchemaField::Boolean { .. } => {
-                    let lowercase = value.to_lowercase();
-                    if !["true", "false", "1", "0", "yes", "no"].contains(&lowercase.as_str()) {
-                        result.add_error(ValidationError::InvalidType(
-                            key.clone(),
-                            "boolean".to_string(),
-                            value.clone(),
+            }
+        }
+        
+        SchemaField::Number {
+            minimum,
+            maximum,
+            exclusive_minimum,
+            exclusive_maximum,
+            multiple_of,
+            integer,
+            ..
+        } => {
+            // `parse` accepts NaN and infinity, which every bound would let through
+            let Some(number) = value.parse::<f64>().ok().filter(|number| number.is_finite()) else {
+                errors.push(ValidationError::InvalidType(
+                    key.to_string(),
+                    "number".to_string(),
+                    value.to_string(),
+                ));
+                return errors;
+            };
+            
+            if *integer && number.fract() != 0.0 {
+                errors.push(ValidationError::NotInteger(key.to_string(), value.to_string()));
+            }
+            if let Some(min) = minimum
+                && number < *min
+            {
+                errors.push(ValidationError::BelowMinimum(key.to_string(), *min, value.to_string()));
+            }
+            if let Some(max) = maximum
+                && number > *max
+            {
+                errors.push(ValidationError::AboveMaximum(key.to_string(), *max, value.to_string()));
+            }
+            if let Some(min) = exclusive_minimum
+                && number <= *min
+            {
+                errors.push(ValidationError::BelowExclusiveMinimum(
+                    key.to_string(),
+                    *min,
+                    value.to_string(),
+                ));
+            }
+            if let Some(max) = exclusive_maximum
+                && number >= *max
+            {
+                errors.push(ValidationError::AboveExclusiveMaximum(
+                    key.to_string(),
+                    *max,
+                    value.to_string(),
+                ));
+            }
+            if let Some(step) = multiple_of
+                && !is_multiple_of(number, *step)
+            {
+                errors.push(ValidationError::NotMultipleOf(
+                    key.to_string(),
+                    *step,
+                    value.to_string(),
+                ));
+            }
+        }
+        
+        SchemaField::Boolean { .. } => {
+            if !is_boolean_literal(value) {
+                errors.push(ValidationError::InvalidType(
+                    key.to_string(),
+                    "boolean".to_string(),
+                    value.to_string(),
+                ));
+            }
+        }
+        
+        SchemaField::Enum { values, .. } => {
+            if !values.iter().any(|allowed| allowed == value) {
+                errors.push(ValidationError::InvalidEnum(
+                    key.to_string(),
+                    values.clone(),
+                    value.to_string(),
+                ));
+            }
+        }
+        
+        SchemaField::Url {
+            schemes,
+            require_host,
+            require_port,
+            require_path,
+            allow_credentials,
+            ..
+        } => {
+            let url = match Url::parse(value) {
+                Ok(url) => url,
+                Err(err) => {
+                    errors.push(ValidationError::InvalidUrl(
+                        key.to_string(),
+                        value.to_string(),
+                        err.to_string(),
+                    ));
+                    return errors;
+                }
+            };
+            
+            if !schemes.is_empty() && !schemes.iter().any(|scheme| scheme == url.scheme()) {
+                errors.push(ValidationError::InvalidUrlScheme(
+                    key.to_string(),
+                    schemes.clone(),
+                    url.scheme().to_string(),
+                ));
+            }
+            if *require_host && url.host_str().is_none_or(str::is_empty) {
+                errors.push(ValidationError::MissingUrlComponent(key.to_string(), "host".to_string()));
+            }
+            if *require_port && url.port_or_known_default().is_none() {
+                errors.push(ValidationError::MissingUrlComponent(key.to_string(), "port".to_string()));
+            }
+            if *require_path && matches!(url.path(), "" | "/") {
+                errors.push(ValidationError::MissingUrlComponent(key.to_string(), "path".to_string()));
+            }
+            if !*allow_credentials && (!url.username().is_empty() || url.password().is_some()) {
+                errors.push(ValidationError::UrlCredentialsNotAllowed(key.to_string()));
+            }
+        }
+        
+        SchemaField::Array {
+            separator,
+            min_items,
+            max_items,
+            unique_items,
+            items,
+            ..
+        } => {
+            let elements = split_items(value, separator);
+            
+            if let Some(min) = min_items
+                && elements.len() < *min
+            {
+                errors.push(ValidationError::TooFewItems(key.to_string(), *min, elements.len()));
+            }
+            if let Some(max) = max_items
+                && elements.len() > *max
+            {
+                errors.push(ValidationError::TooManyItems(key.to_string(), *max, elements.len()));
+            }
+            if *unique_items {
+                let mut seen = HashSet::new();
+                for (index, element) in elements.iter().enumerate() {
+                    if !seen.insert(*element) {
+                        errors.push(ValidationError::DuplicateItem(
+                            key.to_string(),
+                            index,
+                            element.to_string(),
                         ));
                     }
                 }
-                
-                SchemaField::Enum { values, .. } => {
-                    if !values.contains(value) {
-                        result.add_error(ValidationError::InvalidEnum(
-                            key.clone(),
-                            values.clone(),
-                            value.clone(),
+            }
+            if let Some(item_field) = items {
+                for (index, element) in elements.iter().enumerate() {
+                    let item_key = format!("{}[{}]", key, index);
+                    for error in check_value(&item_key, element, item_field, patterns) {
+                        errors.push(ValidationError::InvalidItem(
+                            key.to_string(),
+                            index,
+                            Box::new(error),
                         ));
                     }
                 }
             }
-        } else {
-            if field.is_required() {
-                if field.get_default().is_none() {
-                    result.add_error(ValidationError::MissingRequired(key.clone()));
+        }
+        
+        SchemaField::Json { schema, .. } => {
+            let document: serde_json::Value = match serde_json::from_str(value) {
+                Ok(document) => document,
+                Err(err) => {
+                    errors.push(ValidationError::InvalidJson(
+                        key.to_string(),
+                        value.to_string(),
+                        err.to_string(),
+                    ));
+                    return errors;
+                }
+            };
+            
+            if let Some(schema) = schema {
+                for violation in json_schema::check(schema, &document) {
+                    let node = document.pointer(&violation.pointer).unwrap_or(&document);
+                    errors.push(ValidationError::JsonSchemaMismatch(
+                        key.to_string(),
+                        violation.pointer,
+                        violation.message,
+                        node.to_string(),
+                    ));
                 }
             }
         }
+        
+        SchemaField::Duration { min, max, .. } => {
+            errors.extend(check_quantity(key, value, "duration", parse_duration, min, max));
+        }
+        
+        SchemaField::ByteSize { min, max, .. } => {
+            errors.extend(check_quantity(key, value, "bytesize", parse_byte_size, min, max));
+        }
     }
     
-    if strict {
-        for key in env_vars.keys() {
-            if !processed_keys.contains(key) {
-                result.add_error(ValidationError::ExtraField(key.clone()));
+    errors
+}
+
+/// evaluate a constraint, skipping it when a variable it reads is unset or
+/// does not have a valid value for its field type
+///
+/// `expr` is the already parsed constraint, if there is one; otherwise the
+/// constraint is parsed here.
+fn check_constraint<'a>(
+    key: &str,
+    constraint: &Constraint,
+    expr: Option<&Expr>,
+    schema: &Schema,
+    lookup: &dyn Fn(&str) -> Option<&'a str>,
+) -> Option<ValidationError> {
+    let invalid = |error: ExprError| {
+        ValidationError::InvalidExpression(
+            key.to_string(),
+            constraint.expr.clone(),
+            error.to_string(),
+        )
+    };
+    
+    let parsed;
+    let expr = match expr {
+        Some(expr) => expr,
+        None => match Expr::parse(&constraint.expr) {
+            Ok(expr) => {
+                parsed = expr;
+                &parsed
             }
+            Err(error) => return Some(invalid(error)),
+        },
+    };
+    let typed_lookup = |name: &str| typed_value(schema.get(name), lookup(name)?);
+    
+    match expr.eval_bool(&typed_lookup) {
+        Ok(true) | Err(ExprError::Unset(_)) => None,
+        Ok(false) => Some(ValidationError::ConstraintFailed(
+            key.to_string(),
+            constraint.expr.clone(),
+            constraint.message.clone().unwrap_or_else(|| constraint.expr.clone()),
+        )),
+        Err(error) => Some(invalid(error)),
+    }
+}
+
+/// read a raw value as the type its schema field declares
+///
+/// numbers, booleans, durations (in seconds) and byte sizes (in bytes) are
+/// typed; everything else, including keys without a field, is a string.
+fn typed_value(field: Option<&SchemaField>, raw: &str) -> Option<Value> {
+    match field {
+        Some(SchemaField::Number { .. }) => raw
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .map(Value::Number),
+        Some(SchemaField::Boolean { .. }) => is_boolean_literal(raw)
+            .then(|| Value::Bool(matches!(raw.to_lowercase().as_str(), "true" | "1" | "yes"))),
+        Some(SchemaField::Duration { .. }) => {
+            parse_duration(raw).map(|duration| Value::Number(duration.as_secs_f64()))
+        }
+        Some(SchemaField::ByteSize { .. }) => {
+            parse_byte_size(raw).map(|bytes| Value::Number(bytes as f64))
         }
+        _ => Some(Value::String(raw.to_string())),
     }
+}
+
+/// check a value written with units against bounds written in the same units
+fn check_quantity<T: PartialOrd>(
+    key: &str,
+    value: &str,
+    type_name: &str,
+    parse: fn(&str) -> Option<T>,
+    min: &Option<String>,
+    max: &Option<String>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
     
-    result
+    let Some(quantity) = parse(value) else {
+        errors.push(ValidationError::InvalidType(
+            key.to_string(),
+            type_name.to_string(),
+            value.to_string(),
+        ));
+        return errors;
+    };
+    
+    if let Some(min) = min
+        && parse(min).is_some_and(|min| quantity < min)
+    {
+        errors.push(ValidationError::TooSmall(key.to_string(), min.clone(), value.to_string()));
+    }
+    if let Some(max) = max
+        && parse(max).is_some_and(|max| quantity > max)
+    {
+        errors.push(ValidationError::TooLarge(key.to_string(), max.clone(), value.to_string()));
+    }
+    
+    errors
 }
 
+/// split an array value into trimmed items; an empty value has no items
+fn split_items<'a>(value: &'a str, separator: &str) -> Vec<&'a str> {
+    if value.trim().is_empty() {
+        return Vec::new();
+    }
+    if separator.is_empty() {
+        return vec![value.trim()];
+    }
+    value.split(separator).map(str::trim).collect()
+}
 
+fn is_multiple_of(number: f64, step: f64) -> bool {
+    if step == 0.0 {
+        return false;
+    }
+    let quotient = number / step;
+    (quotient - quotient.round()).abs() < 1e-9
+}
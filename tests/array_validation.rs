@@ -0,0 +1,96 @@
+use dotenvcrab::validation::ValidationError;
+mod test_helpers;
+use test_helpers::validate_env_with_schema;
+
+#[test]
+fn test_array_valid() {
+    let schema_str = r#"{
+        "ALLOWED_ORIGINS": {
+            "type": "array",
+            "minItems": 1,
+            "uniqueItems": true,
+            "items": { "type": "string", "format": "url" }
+        },
+        "KAFKA_BROKERS": { "type": "array", "separator": ";" }
+    }"#;
+    
+    let result = validate_env_with_schema(
+        "ALLOWED_ORIGINS=https://a.com, https://b.com\nKAFKA_BROKERS=h1:9092;h2:9092",
+        schema_str,
+        false,
+    );
+    assert!(result.is_valid, "{:?}", result.errors);
+}
+
+#[test]
+fn test_array_item_count() {
+    let schema_str = r#"{
+        "KAFKA_BROKERS": { "type": "array", "minItems": 2, "maxItems": 3 }
+    }"#;
+    
+    let result = validate_env_with_schema("KAFKA_BROKERS=h1:9092", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooFewItems(_, 2, 1)]));
+    
+    let result = validate_env_with_schema("KAFKA_BROKERS=", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooFewItems(_, 2, 0)]));
+    
+    let result = validate_env_with_schema("KAFKA_BROKERS=a,b,c,d", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooManyItems(_, 3, 4)]));
+    assert_eq!(
+        result.errors[0].to_string(),
+        "KAFKA_BROKERS must have at most 3 items, got 4"
+    );
+}
+
+#[test]
+fn test_array_unique_items() {
+    let schema_str = r#"{ "REGIONS": { "type": "array", "uniqueItems": true } }"#;
+    
+    let result = validate_env_with_schema("REGIONS=eu,us,eu", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::DuplicateItem(key, index, item)] => {
+            assert_eq!(key, "REGIONS");
+            assert_eq!(*index, 2);
+            assert_eq!(item, "eu");
+        }
+        errors => panic!("Expected DuplicateItem error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_array_item_errors_report_index() {
+    let schema_str = r#"{
+        "PORTS": {
+            "type": "array",
+            "items": { "type": "number", "integer": true, "minimum": 1 }
+        },
+        "LEVELS": {
+            "type": "array",
+            "items": { "type": "enum", "values": ["debug", "info"] }
+        },
+        "FLAGS": { "type": "array", "items": { "type": "boolean" } }
+    }"#;
+    
+    let result = validate_env_with_schema("PORTS=80,abc,0", schema_str, false);
+    assert_eq!(result.errors.len(), 2);
+    match &result.errors[0] {
+        ValidationError::InvalidItem(key, index, inner) => {
+            assert_eq!(key, "PORTS");
+            assert_eq!(*index, 1);
+            assert!(matches!(**inner, ValidationError::InvalidType(..)));
+        }
+        error => panic!("Expected InvalidItem error, got {:?}", error),
+    }
+    assert!(matches!(&result.errors[1], ValidationError::InvalidItem(_, 2, _)));
+    assert_eq!(
+        result.errors[0].to_string(),
+        "invalid type for PORTS[1]: expected number, got abc"
+    );
+    
+    let result = validate_env_with_schema("LEVELS=debug,trace", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::InvalidItem(_, 1, _)]));
+    
+    let result = validate_env_with_schema("FLAGS=true,maybe", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::InvalidItem(_, 1, _)]));
+    assert_eq!(result.errors[0].code(), "invalid_item");
+}
@@ -1,4 +1,4 @@
-use dotenvcrab::cli::Cli;
+use dotenvcrab::cli::{Cli, Command};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -6,11 +6,12 @@ use std::path::PathBuf;
 fn test_cli_default_values() {
     let cli = Cli::parse_from(["dotenvcrab"]);
     
-    assert_eq!(cli.env, PathBuf::from(".env"));
+    assert_eq!(cli.env, vec![PathBuf::from(".env")]);
     assert_eq!(cli.schema, PathBuf::from("env.schema.json"));
     assert!(!cli.strict);
     assert!(!cli.json);
     assert!(!cli.generate);
+    assert!(!cli.force);
 }
 
 #[test]
@@ -24,7 +25,7 @@ fn test_cli_custom_values() {
         "--generate",
     ]);
     
-    assert_eq!(cli.env, PathBuf::from("custom.env"));
+    assert_eq!(cli.env, vec![PathBuf::from("custom.env")]);
     assert_eq!(cli.schema, PathBuf::from("custom.schema.json"));
     assert!(cli.strict);
     assert!(cli.json);
@@ -42,7 +43,7 @@ fn test_cli_short_options() {
         "-g",
     ]);
     
-    assert_eq!(cli.env, PathBuf::from("custom.env"));
+    assert_eq!(cli.env, vec![PathBuf::from("custom.env")]);
     assert_eq!(cli.schema, PathBuf::from("custom.schema.json"));
     assert!(cli.strict);
     assert!(cli.json);
@@ -59,9 +60,69 @@ fn test_cli_mixed_options() {
         "--json",
     ]);
     
-    assert_eq!(cli.env, PathBuf::from("custom.env"));
+    assert_eq!(cli.env, vec![PathBuf::from("custom.env")]);
     assert_eq!(cli.schema, PathBuf::from("custom.schema.json"));
     assert!(cli.strict);
     assert!(cli.json);
     assert!(!cli.generate);
 }
+
+#[test]
+fn test_cli_force_requires_generate() {
+    let cli = Cli::parse_from(["dotenvcrab", "--generate", "--force"]);
+    assert!(cli.generate);
+    assert!(cli.force);
+    
+    assert!(Cli::try_parse_from(["dotenvcrab", "--force"]).is_err());
+}
+
+#[test]
+fn test_cli_generate_example_subcommand() {
+    let cli = Cli::parse_from(["dotenvcrab", "generate-example", "-s", "custom.schema.json"]);
+    
+    assert_eq!(cli.schema, PathBuf::from("custom.schema.json"));
+    match cli.command {
+        Some(Command::GenerateExample { output, force }) => {
+            assert_eq!(output, PathBuf::from(".env.example"));
+            assert!(!force);
+        }
+        _ => panic!("Expected GenerateExample command"),
+    }
+}
+
+#[test]
+fn test_cli_process_env_options() {
+    let cli = Cli::parse_from([
+        "dotenvcrab",
+        "--from-process-env",
+        "--prefix", "APP_,SVC_",
+        "--allow", "SENTRY_DSN",
+    ]);
+    
+    assert!(cli.from_process_env);
+    assert_eq!(cli.prefix, vec!["APP_".to_string(), "SVC_".to_string()]);
+    assert_eq!(cli.allow, vec!["SENTRY_DSN".to_string()]);
+    
+    assert!(Cli::try_parse_from(["dotenvcrab", "--prefix", "APP_"]).is_err());
+    assert!(Cli::try_parse_from(["dotenvcrab", "--from-process-env", "--env", "x.env"]).is_err());
+}
+
+#[test]
+fn test_cli_layered_env_files() {
+    let cli = Cli::parse_from([
+        "dotenvcrab",
+        "-e", ".env,.env.local",
+        "--env", ".env.ci",
+        "--mode", "production",
+    ]);
+    
+    assert_eq!(
+        cli.env,
+        vec![
+            PathBuf::from(".env"),
+            PathBuf::from(".env.local"),
+            PathBuf::from(".env.ci"),
+        ]
+    );
+    assert_eq!(cli.mode.as_deref(), Some("production"));
+}
@@ -0,0 +1,174 @@
+use dotenvcrab::compiled::{CompileError, CompiledSchema};
+use dotenvcrab::expr::{Expr, ExprError, Value};
+use dotenvcrab::validation::{validate_compiled, ValidationError};
+mod test_helpers;
+use test_helpers::{load_schema_from_str, parse_env_str, validate_env_with_schema};
+
+fn eval(source: &str, vars: &[(&str, Value)]) -> Result<bool, ExprError> {
+    let lookup = |name: &str| {
+        vars.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.clone())
+    };
+    Expr::parse(source)?.eval_bool(&lookup)
+}
+
+#[test]
+fn test_expression_evaluation() {
+    let vars = [
+        ("MIN", Value::Number(2.0)),
+        ("MAX", Value::Number(10.0)),
+        ("ENV", Value::String("production".to_string())),
+        ("DEBUG", Value::Bool(false)),
+    ];
+    
+    assert_eq!(eval("MIN <= MAX", &vars), Ok(true));
+    assert_eq!(eval("MIN > MAX || MAX == 10", &vars), Ok(true));
+    assert_eq!(eval("!(ENV == 'production') && true", &vars), Ok(false));
+    assert_eq!(eval("ENV != \"dev\" && !DEBUG", &vars), Ok(true));
+    assert_eq!(eval("startsWith(ENV, 'prod') && endsWith(ENV, 'tion')", &vars), Ok(true));
+    assert_eq!(eval("contains(ENV, 'duc') && len(ENV) == 10", &vars), Ok(true));
+    assert_eq!(eval("MIN >= -1", &vars), Ok(true));
+}
+
+#[test]
+fn test_expression_short_circuits_unset_variables() {
+    let vars = [("ENV", Value::String("dev".to_string()))];
+    
+    assert_eq!(eval("ENV != 'production' || startsWith(PUBLIC_URL, 'https://')", &vars), Ok(true));
+    assert_eq!(eval("ENV == 'dev' && PUBLIC_URL == ''", &vars), Err(ExprError::Unset("PUBLIC_URL".to_string())));
+}
+
+#[test]
+fn test_expression_errors() {
+    assert!(matches!(Expr::parse("MIN <"), Err(ExprError::Syntax(..))));
+    assert!(matches!(Expr::parse("(MIN < MAX"), Err(ExprError::Syntax(..))));
+    assert!(matches!(Expr::parse("MIN MAX"), Err(ExprError::Syntax(..))));
+    assert!(matches!(Expr::parse("len(A, B)"), Err(ExprError::Syntax(..))));
+    assert_eq!(Expr::parse("exec('rm')"), Err(ExprError::UnknownFunction("exec".to_string())));
+    
+    assert!(matches!(eval("'a' < 'b'", &[]), Err(ExprError::Type(_))));
+    assert!(matches!(eval("1 == 'a'", &[]), Err(ExprError::Type(_))));
+    assert!(matches!(eval("len('abc')", &[]), Err(ExprError::Type(_))));
+}
+
+#[test]
+fn test_constraints_use_typed_fields() {
+    let schema_str = r#"{
+        "POOL_MIN": {
+            "type": "number",
+            "constraints": [
+                { "expr": "POOL_MIN <= POOL_MAX", "message": "POOL_MIN must not exceed POOL_MAX" }
+            ]
+        },
+        "POOL_MAX": { "type": "number", "default": 10 },
+        "READ_TIMEOUT": {
+            "type": "duration",
+            "constraints": [{ "expr": "READ_TIMEOUT < REQUEST_TIMEOUT" }]
+        },
+        "REQUEST_TIMEOUT": { "type": "duration", "default": "30s" }
+    }"#;
+    
+    assert!(validate_env_with_schema("POOL_MIN=9\nREAD_TIMEOUT=5s", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("POOL_MIN=20\nPOOL_MAX=15\nREAD_TIMEOUT=1m", schema_str, false);
+    assert_eq!(result.errors.len(), 2);
+    match &result.errors[0] {
+        ValidationError::ConstraintFailed(key, expr, message) => {
+            assert_eq!(key, "POOL_MIN");
+            assert_eq!(expr, "POOL_MIN <= POOL_MAX");
+            assert_eq!(message, "POOL_MIN must not exceed POOL_MAX");
+        }
+        error => panic!("Expected ConstraintFailed error, got {:?}", error),
+    }
+    assert_eq!(
+        result.errors[0].to_string(),
+        "constraint on POOL_MIN failed: POOL_MIN must not exceed POOL_MAX"
+    );
+    assert_eq!(
+        result.errors[1].to_string(),
+        "constraint on READ_TIMEOUT failed: READ_TIMEOUT < REQUEST_TIMEOUT"
+    );
+}
+
+#[test]
+fn test_constraint_skipped_for_invalid_or_unset_values() {
+    let schema_str = r#"{
+        "POOL_MIN": { "type": "number", "constraints": [{ "expr": "POOL_MIN <= POOL_MAX" }] },
+        "POOL_MAX": { "type": "number" }
+    }"#;
+    
+    assert!(validate_env_with_schema("POOL_MIN=5", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("POOL_MIN=5\nPOOL_MAX=lots", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::InvalidType(..)]));
+}
+
+#[test]
+fn test_conditional_constraint() {
+    let schema_str = r#"{
+        "ENV": { "type": "enum", "values": ["development", "production"] },
+        "PUBLIC_URL": {
+            "type": "url",
+            "constraints": [{
+                "expr": "ENV != 'production' || startsWith(PUBLIC_URL, 'https://')",
+                "message": "PUBLIC_URL must use https in production"
+            }]
+        }
+    }"#;
+    
+    assert!(validate_env_with_schema("ENV=development\nPUBLIC_URL=http://localhost", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("ENV=production\nPUBLIC_URL=https://crab.dev", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("ENV=production\nPUBLIC_URL=http://crab.dev", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::ConstraintFailed(..)]));
+}
+
+#[test]
+fn test_invalid_expression_reported() {
+    let schema_str = r#"{
+        "A": { "type": "string", "constraints": [{ "expr": "A <" }] }
+    }"#;
+    
+    let result = validate_env_with_schema("A=x", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::InvalidExpression(key, expr, _)] => {
+            assert_eq!(key, "A");
+            assert_eq!(expr, "A <");
+        }
+        errors => panic!("Expected InvalidExpression error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_compiled_schema_parses_constraints_once() {
+    let schema = load_schema_from_str(r#"{
+        "MIN": { "type": "number", "constraints": [{ "expr": "MIN <= MAX" }] },
+        "MAX": { "type": "number", "constraints": [{ "expr": "MIN <= MAX" }, { "expr": "MAX < 100" }] }
+    }"#).unwrap();
+    let (compiled, errors) = CompiledSchema::compile(schema);
+    assert!(errors.is_empty());
+    assert_eq!(compiled.expressions().len(), 2);
+    
+    assert!(validate_compiled(&parse_env_str("MIN=1\nMAX=2").unwrap(), &compiled, false).is_valid);
+    let result = validate_compiled(&parse_env_str("MIN=300\nMAX=200").unwrap(), &compiled, false);
+    assert_eq!(result.errors.len(), 3);
+    assert!(result.errors.iter().all(|error| matches!(error, ValidationError::ConstraintFailed(..))));
+}
+
+#[test]
+fn test_compile_rejects_invalid_expression() {
+    let schema = load_schema_from_str(r#"{
+        "A": { "type": "string", "constraints": [{ "expr": "A <" }] }
+    }"#).unwrap();
+    
+    let (compiled, errors) = CompiledSchema::compile(schema);
+    assert!(compiled.expressions().is_empty());
+    match &errors[..] {
+        [CompileError::InvalidExpression(key, expr, ExprError::Syntax(..))] => {
+            assert_eq!(key, "A");
+            assert_eq!(expr, "A <");
+        }
+        errors => panic!("Expected one InvalidExpression, got {:?}", errors),
+    }
+}
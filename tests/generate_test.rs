@@ -0,0 +1,188 @@
+use dotenvcrab::generate::{
+    generate_example, generate_schema, infer_field, schema_to_json, write_example, write_schema,
+    GenerateError,
+};
+use dotenvcrab::loader::load_env_file;
+use dotenvcrab::schema::{load_schema, SchemaField};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+mod test_helpers;
+use test_helpers::{load_schema_from_str, run_dotenvcrab};
+
+#[test]
+fn test_infer_field_types() {
+    assert!(matches!(infer_field("8080"), SchemaField::Number { required: true, .. }));
+    assert!(matches!(infer_field("3.14"), SchemaField::Number { .. }));
+    assert!(matches!(infer_field("true"), SchemaField::Boolean { required: true, .. }));
+    assert!(matches!(infer_field("No"), SchemaField::Boolean { .. }));
+    assert!(matches!(infer_field("https://example.com"), SchemaField::String { required: true, .. }));
+    assert!(matches!(infer_field(""), SchemaField::String { .. }));
+    for value in ["NaN", "inf", "-infinity"] {
+        assert!(matches!(infer_field(value), SchemaField::String { .. }), "{}", value);
+    }
+}
+
+#[test]
+fn test_infer_field_prefers_number_for_numeric_booleans() {
+    assert!(matches!(infer_field("1"), SchemaField::Number { .. }));
+    assert!(matches!(infer_field("0"), SchemaField::Number { .. }));
+}
+
+#[test]
+fn test_generate_schema_round_trip() {
+    let mut env_vars = HashMap::new();
+    env_vars.insert("PORT".to_string(), "8080".to_string());
+    env_vars.insert("DEBUG".to_string(), "false".to_string());
+    env_vars.insert("API_URL".to_string(), "https://api.example.com".to_string());
+    
+    let schema = generate_schema(&env_vars);
+    assert_eq!(schema.len(), 3);
+    
+    let json = schema_to_json(&schema).unwrap();
+    let api_pos = json.find("API_URL").unwrap();
+    let debug_pos = json.find("DEBUG").unwrap();
+    let port_pos = json.find("PORT").unwrap();
+    assert!(api_pos < debug_pos && debug_pos < port_pos);
+    assert!(!json.contains("null"));
+    
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    write_schema(&schema, &path, false).unwrap();
+    
+    let loaded = load_schema(&path).unwrap();
+    assert!(matches!(loaded["PORT"], SchemaField::Number { .. }));
+    assert!(matches!(loaded["DEBUG"], SchemaField::Boolean { .. }));
+    assert!(matches!(loaded["API_URL"], SchemaField::String { .. }));
+}
+
+#[test]
+fn test_write_schema_refuses_to_overwrite() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, "{}").unwrap();
+    
+    let schema = generate_schema(&HashMap::from([("PORT".to_string(), "80".to_string())]));
+    
+    let err = write_schema(&schema, &path, false).unwrap_err();
+    assert!(matches!(err, GenerateError::AlreadyExists(_)));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+    
+    write_schema(&schema, &path, true).unwrap();
+    assert!(fs::read_to_string(&path).unwrap().contains("PORT"));
+}
+
+#[test]
+fn test_generate_cli() {
+    let dir = tempdir().unwrap();
+    let env_path = dir.path().join(".env");
+    let schema_path = dir.path().join("env.schema.json");
+    fs::write(&env_path, "PORT=8080\nDEBUG=true\nNAME=crab\n").unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--generate",
+    ]);
+    
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("3 field(s)"));
+    
+    let schema = load_schema(&schema_path).unwrap();
+    assert_eq!(schema.len(), 3);
+    
+    let output = run_dotenvcrab(&[
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--generate",
+    ]);
+    
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("already exists"));
+    
+    let output = run_dotenvcrab(&[
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--generate",
+        "--force",
+    ]);
+    
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_generate_example() {
+    let schema = load_schema_from_str(r#"{
+        "PORT": { "type": "number", "required": true, "description": "Port to listen on" },
+        "LOG_LEVEL": {
+            "type": "enum",
+            "values": ["debug", "info", "warn"],
+            "default": "info"
+        },
+        "EMAIL": { "type": "string", "pattern": "^[^@]+@[^@]+$" },
+        "GREETING": { "type": "string", "default": "hello world" }
+    }"#).unwrap();
+    
+    let example = generate_example(&schema);
+    
+    assert_eq!(
+        example,
+        "# pattern: ^[^@]+@[^@]+$\n\
+         EMAIL=\n\
+         \n\
+         GREETING='hello world'\n\
+         \n\
+         # one of: debug, info, warn\n\
+         LOG_LEVEL=info\n\
+         \n\
+         # Port to listen on\n\
+         # (required)\n\
+         PORT=\n"
+    );
+}
+
+#[test]
+fn test_generate_example_is_parseable() {
+    let schema = load_schema_from_str(r#"{
+        "QUOTED": { "type": "string", "default": "it's a \"test\"" },
+        "DOLLAR": { "type": "string", "default": "$HOME/app" },
+        "QUOTED_DOLLAR": { "type": "string", "default": "it's $HOME and ${USER:-me}" },
+        "DEBUG": { "type": "boolean", "default": false }
+    }"#).unwrap();
+    
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(".env.example");
+    write_example(&schema, &path, false).unwrap();
+    
+    let parsed = load_env_file(&path).unwrap();
+    
+    assert_eq!(parsed["QUOTED"], "it's a \"test\"");
+    assert_eq!(parsed["DOLLAR"], "$HOME/app");
+    assert_eq!(parsed["QUOTED_DOLLAR"], "it's $HOME and ${USER:-me}");
+    assert_eq!(parsed["DEBUG"], "false");
+    
+    assert!(matches!(
+        write_example(&schema, &path, false),
+        Err(GenerateError::AlreadyExists(_))
+    ));
+}
+
+#[test]
+fn test_generate_example_cli() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let example_path = dir.path().join(".env.example");
+    fs::write(&schema_path, r#"{ "PORT": { "type": "number", "default": 8080 } }"#).unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "generate-example",
+        "--schema", schema_path.to_str().unwrap(),
+        "--output", example_path.to_str().unwrap(),
+    ]);
+    
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&example_path).unwrap(), "PORT=8080\n");
+}
@@ -102,10 +102,10 @@ fn test_invalid_regex_pattern() {
     ]);
     
     assert!(!output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("Invalid .env"));
-    assert!(stdout.contains("EMAIL"));
-    assert!(stdout.contains("invalid regex pattern"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to load schema"));
+    assert!(stderr.contains("EMAIL"));
+    assert!(stderr.contains("invalid regex pattern"));
 }
 
 #[test]
@@ -207,6 +207,65 @@ fn test_json_output() {
     assert!(json["errors"].as_array().unwrap().is_empty());
 }
 
+#[test]
+fn test_structured_json_output() {
+    let dir = tempdir().unwrap();
+    
+    let schema_str = r#"{
+        "PORT": { "type": "number", "required": true }
+    }"#;
+    
+    let schema_path = create_schema_file(&dir, schema_str);
+    let env_path = create_env_file(&dir, "PORT=abc");
+    
+    let output = run_dotenvcrab(&[
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--format", "json",
+    ]);
+    
+    assert!(!output.status.success());
+    
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["valid"], false);
+    assert_eq!(json["errors"][0]["code"], "invalid_type");
+    assert_eq!(json["errors"][0]["key"], "PORT");
+    assert_eq!(json["errors"][0]["expected"], "number");
+    assert_eq!(json["errors"][0]["actual"], "abc");
+    assert_eq!(json["errors"][0]["location"]["line"], 1);
+}
+
+#[test]
+fn test_junit_output_to_file() {
+    let dir = tempdir().unwrap();
+    
+    let schema_str = r#"{
+        "PORT": { "type": "number", "required": true },
+        "DEBUG": { "type": "boolean", "required": true }
+    }"#;
+    
+    let schema_path = create_schema_file(&dir, schema_str);
+    let env_path = create_env_file(&dir, "PORT=8080\nDEBUG=maybe");
+    let report_path = dir.path().join("report.xml");
+    
+    let output = run_dotenvcrab(&[
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--format", "junit",
+        "--output", report_path.to_str().unwrap(),
+    ]);
+    
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    assert!(report.contains("tests=\"2\" failures=\"1\""));
+    assert!(report.contains("<testcase name=\"PORT\" classname=\"dotenvcrab\"/>"));
+    assert!(report.contains("<failure type=\"invalid_type\""));
+    assert!(report.contains("expected: boolean\nactual: maybe"));
+}
+
 #[test]
 fn test_missing_env_file() {
     let dir = tempdir().unwrap();
@@ -0,0 +1,149 @@
+use dotenvcrab::json_schema;
+use dotenvcrab::schema::{load_schema, load_schema_with_locations, SchemaError, SchemaField, StringFormat};
+use dotenvcrab::validation::{validate_env, ValidationError};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+mod test_helpers;
+use test_helpers::{load_schema_from_str, run_dotenvcrab};
+
+const CONFIG_SCHEMA: &str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "service config",
+  "type": "object",
+  "properties": {
+    "PORT": { "type": "integer", "minimum": 1024, "maximum": 65535, "default": 8080 },
+    "LOG_LEVEL": { "enum": ["debug", "info", "warn"], "default": "info" },
+    "API_KEY": { "type": "string", "pattern": "^sk_", "description": "key for the billing API" },
+    "CALLBACK_URL": { "type": "string", "format": "uri" },
+    "DEBUG": { "type": "boolean", "default": false }
+  },
+  "required": ["API_KEY"]
+}"#;
+
+#[test]
+fn test_json_schema_is_translated() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, CONFIG_SCHEMA).unwrap();
+    
+    let loaded = load_schema_with_locations(&path).unwrap();
+    let schema = &loaded.schema;
+    assert_eq!(schema.len(), 5);
+    assert!(matches!(
+        schema["PORT"],
+        SchemaField::Number { required: false, default: Some(8080.0), minimum: Some(1024.0), integer: true, .. }
+    ));
+    assert!(matches!(&schema["LOG_LEVEL"], SchemaField::Enum { values, .. } if values.len() == 3));
+    assert_eq!(schema["LOG_LEVEL"].get_default(), Some("info".to_string()));
+    assert!(matches!(&schema["API_KEY"], SchemaField::String { required: true, pattern: Some(p), .. } if p == "^sk_"));
+    assert_eq!(schema["API_KEY"].description(), Some(&"key for the billing API".to_string()));
+    assert!(matches!(schema["CALLBACK_URL"], SchemaField::String { format: Some(StringFormat::Url), .. }));
+    assert!(matches!(schema["DEBUG"], SchemaField::Boolean { default: Some(false), .. }));
+    assert_eq!(loaded.locations["PORT"].line, 6);
+    
+    let env: HashMap<String, String> = [("PORT", "80"), ("API_KEY", "pk_live")]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let result = validate_env(&env, schema, false);
+    assert_eq!(result.errors.len(), 2);
+    assert!(result.errors.iter().any(|err| matches!(err, ValidationError::InvalidPattern(key, ..) if key == "API_KEY")));
+}
+
+#[test]
+fn test_json_schema_in_yaml() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.yaml");
+    fs::write(&path, "type: object\nproperties:\n  PORT:\n    type: number\n    minimum: 1\nrequired: [PORT]\n").unwrap();
+    
+    let loaded = load_schema_with_locations(&path).unwrap();
+    assert!(matches!(loaded.schema["PORT"], SchemaField::Number { required: true, minimum: Some(min), .. } if min == 1.0));
+    assert_eq!(loaded.locations["PORT"].line, 3);
+}
+
+#[test]
+fn test_json_schema_unmapped_keywords_are_reported() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, r#"{
+  "type": "object",
+  "additionalProperties": false,
+  "properties": {
+    "HOSTS": { "type": "array", "items": { "type": "string" } },
+    "PORT": { "type": "integer", "minimum": 1, "oneOf": [] },
+    "NAME": { "type": "string", "minimum": 3 }
+  }
+}"#).unwrap();
+    
+    match load_schema(&path).unwrap_err() {
+        SchemaError::UnmappedKeywords(file, keywords) => {
+            assert_eq!(file, path);
+            assert_eq!(keywords, [
+                "/additionalProperties",
+                "/properties/HOSTS/items",
+                "/properties/HOSTS/type",
+                "/properties/NAME/minimum",
+                "/properties/PORT/oneOf",
+            ]);
+        }
+        err => panic!("Expected UnmappedKeywords, got {}", err),
+    }
+}
+
+#[test]
+fn test_export_json_schema() {
+    let schema = load_schema_from_str(r#"{
+        "PORT": { "type": "number", "integer": true, "required": true, "description": "port to listen on" },
+        "RATIO": { "type": "number", "default": 0.5 },
+        "DEBUG": { "type": "boolean", "default": false },
+        "LOG_LEVEL": { "type": "enum", "values": ["debug", "info"] },
+        "TIMEOUT": { "type": "duration", "default": "30s" },
+        "CACHE_SIZE": { "type": "bytesize" },
+        "CALLBACK_URL": { "type": "url" },
+        "FEATURES": { "type": "json", "schema": { "type": "object" } }
+    }"#).unwrap();
+    
+    let exported = json_schema::export(&schema);
+    assert_eq!(exported["$schema"], json_schema::DRAFT_2020_12);
+    assert_eq!(exported["required"], serde_json::json!(["PORT"]));
+    
+    let properties = &exported["properties"];
+    assert!(properties.as_object().unwrap().values().all(|property| property["type"] == "string"));
+    assert_eq!(properties["PORT"]["description"], "port to listen on");
+    assert_eq!(properties["RATIO"]["default"], "0.5");
+    assert_eq!(properties["DEBUG"]["default"], "false");
+    assert_eq!(properties["LOG_LEVEL"]["enum"], serde_json::json!(["debug", "info"]));
+    assert_eq!(properties["CALLBACK_URL"]["format"], "uri");
+    assert_eq!(properties["FEATURES"]["contentSchema"], serde_json::json!({ "type": "object" }));
+    
+    let valid = serde_json::json!({
+        "PORT": "8080", "RATIO": "1.5e3", "DEBUG": "Yes", "LOG_LEVEL": "info",
+        "TIMEOUT": "1m30s", "CACHE_SIZE": "512 MiB"
+    });
+    assert_eq!(json_schema::check(&exported, &valid), []);
+    
+    let invalid = serde_json::json!({
+        "PORT": "80.5", "RATIO": "abc", "DEBUG": "maybe", "TIMEOUT": "30", "CACHE_SIZE": "5 bananas"
+    });
+    let pointers: Vec<String> = json_schema::check(&exported, &invalid)
+        .into_iter()
+        .map(|violation| violation.pointer)
+        .collect();
+    assert_eq!(pointers, ["/CACHE_SIZE", "/DEBUG", "/PORT", "/RATIO", "/TIMEOUT"]);
+}
+
+#[test]
+fn test_export_jsonschema_command() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, r#"{ "PORT": { "type": "number", "required": true } }"#).unwrap();
+    
+    let output = run_dotenvcrab(&["export-jsonschema", "--schema", path.to_str().unwrap()]);
+    
+    assert!(output.status.success());
+    let exported: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported["type"], "object");
+    assert_eq!(exported["required"], serde_json::json!(["PORT"]));
+    assert_eq!(exported["properties"]["PORT"]["type"], "string");
+}
@@ -0,0 +1,121 @@
+use dotenvcrab::json_schema::{check, Violation};
+use dotenvcrab::validation::ValidationError;
+use serde_json::json;
+mod test_helpers;
+use test_helpers::validate_env_with_schema;
+
+#[test]
+fn test_json_parse() {
+    let schema_str = r#"{ "FEATURE_FLAGS": { "type": "json" } }"#;
+    
+    assert!(validate_env_with_schema(r#"FEATURE_FLAGS={"beta": true}"#, schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema(r#"FEATURE_FLAGS={"beta": }"#, schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::InvalidJson(key, ..)] => assert_eq!(key, "FEATURE_FLAGS"),
+        errors => panic!("Expected InvalidJson error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_json_schema_mismatch_reports_pointer() {
+    let schema_str = r#"{
+        "FEATURE_FLAGS": {
+            "type": "json",
+            "schema": {
+                "type": "object",
+                "additionalProperties": { "type": "boolean" }
+            }
+        }
+    }"#;
+    
+    let result = validate_env_with_schema(
+        r#"FEATURE_FLAGS={"beta": true, "dark/mode": "yes"}"#,
+        schema_str,
+        false,
+    );
+    match &result.errors[..] {
+        [ValidationError::JsonSchemaMismatch(key, pointer, message, node)] => {
+            assert_eq!(key, "FEATURE_FLAGS");
+            assert_eq!(pointer, "/dark~1mode");
+            assert_eq!(message, "expected boolean, got string");
+            assert_eq!(node, r#""yes""#);
+        }
+        errors => panic!("Expected JsonSchemaMismatch error, got {:?}", errors),
+    }
+    assert_eq!(
+        result.errors[0].to_string(),
+        r#"JSON for FEATURE_FLAGS does not match schema at "/dark~1mode": expected boolean, got string"#
+    );
+}
+
+#[test]
+fn test_check_object_keywords() {
+    let schema = json!({
+        "type": "object",
+        "required": ["type", "project_id"],
+        "properties": {
+            "type": { "const": "service_account" },
+            "project_id": { "type": "string", "minLength": 1 }
+        },
+        "additionalProperties": false
+    });
+    
+    assert!(check(&schema, &json!({ "type": "service_account", "project_id": "crab" })).is_empty());
+    
+    let violations = check(&schema, &json!({ "type": "user", "extra": 1 }));
+    assert_eq!(
+        violations,
+        vec![
+            Violation {
+                pointer: String::new(),
+                message: "missing required property project_id".to_string(),
+            },
+            Violation {
+                pointer: "/extra".to_string(),
+                message: "property is not allowed".to_string(),
+            },
+            Violation {
+                pointer: "/type".to_string(),
+                message: r#"expected "service_account", got "user""#.to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_check_array_and_number_keywords() {
+    let schema = json!({
+        "type": "array",
+        "minItems": 1,
+        "uniqueItems": true,
+        "items": { "type": "integer", "minimum": 1, "maximum": 10 }
+    });
+    
+    assert!(check(&schema, &json!([1, 2, 3])).is_empty());
+    
+    let pointers: Vec<String> = check(&schema, &json!([1, 1, 20, 2.5]))
+        .into_iter()
+        .map(|violation| violation.pointer)
+        .collect();
+    assert_eq!(pointers, vec!["/1", "/2", "/3"]);
+    
+    assert_eq!(check(&schema, &json!([])).len(), 1);
+}
+
+#[test]
+fn test_check_combinators() {
+    let schema = json!({
+        "oneOf": [
+            { "type": "string", "pattern": "^[a-z]+$" },
+            { "type": "number" }
+        ],
+        "not": { "const": "admin" }
+    });
+    
+    assert!(check(&schema, &json!("crab")).is_empty());
+    assert!(check(&schema, &json!(42)).is_empty());
+    assert_eq!(check(&schema, &json!("Crab")).len(), 1);
+    assert_eq!(check(&schema, &json!("admin")).len(), 1);
+    assert_eq!(check(&schema, &json!(true)).len(), 1);
+}
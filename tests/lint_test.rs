@@ -0,0 +1,116 @@
+use dotenvcrab::compiled::CompileError;
+use dotenvcrab::lint::{is_shell_name, lint, LintIssue};
+use dotenvcrab::schema::{load_schema, load_schema_with_locations, SchemaError};
+use dotenvcrab::validation::ValidationError;
+use std::fs;
+use tempfile::tempdir;
+mod test_helpers;
+use test_helpers::{load_schema_from_str, run_dotenvcrab};
+
+#[test]
+fn test_is_shell_name() {
+    assert!(is_shell_name("DATABASE_URL"));
+    assert!(is_shell_name("_private"));
+    assert!(is_shell_name("v2"));
+    assert!(!is_shell_name("2FA_SECRET"));
+    assert!(!is_shell_name("my-var"));
+    assert!(!is_shell_name("app.port"));
+    assert!(!is_shell_name(""));
+}
+
+#[test]
+fn test_lint_finds_schema_mistakes() {
+    let schema = load_schema_from_str(r#"{
+        "EMAIL": { "type": "string", "pattern": "[a-z++" },
+        "NAME": { "type": "string", "pattern": "^[a-z]+$", "default": "Admin" },
+        "LOG_LEVEL": { "type": "enum", "values": ["debug", "info", "debug"], "default": "trace" },
+        "MODE": { "type": "enum", "values": [] },
+        "PORT": { "type": "number", "required": true, "default": 8080 },
+        "app-name": { "type": "string" },
+        "TAGS": { "type": "array", "items": { "type": "string", "pattern": "(" } },
+        "POOL": { "type": "number", "constraints": [{ "expr": "POOL <<= 1" }, { "expr": "POOL > 0" }] }
+    }"#).unwrap();
+    
+    let (_, issues) = lint(schema);
+    let summary: Vec<(&str, bool)> = issues.iter().map(|issue| (issue.key(), issue.is_error())).collect();
+    assert_eq!(summary, [
+        ("EMAIL", true),
+        ("LOG_LEVEL", false),
+        ("LOG_LEVEL", true),
+        ("MODE", true),
+        ("NAME", true),
+        ("POOL", true),
+        ("PORT", false),
+        ("TAGS", true),
+        ("app-name", false),
+    ]);
+    
+    assert!(matches!(&issues[0], LintIssue::Compile(CompileError::InvalidPattern(..))));
+    assert!(matches!(&issues[1], LintIssue::DuplicateEnumValue(_, value) if value == "debug"));
+    assert!(matches!(&issues[2], LintIssue::InvalidDefault(_, ValidationError::InvalidEnum(..))));
+    assert!(matches!(&issues[3], LintIssue::EmptyEnum(..)));
+    assert!(matches!(&issues[4], LintIssue::InvalidDefault(_, ValidationError::InvalidPattern(..))));
+    assert!(matches!(&issues[5], LintIssue::Compile(CompileError::InvalidExpression(_, expr, _)) if expr == "POOL <<= 1"));
+    assert!(matches!(&issues[6], LintIssue::RequiredWithDefault(..)));
+    assert!(matches!(&issues[8], LintIssue::InvalidKeyName(..)));
+}
+
+#[test]
+fn test_clean_schema_has_no_issues() {
+    let schema = load_schema_from_str(r#"{
+        "PORT": { "type": "number", "minimum": 1, "default": 8080 },
+        "NAME": { "type": "string", "pattern": "^[a-z]+$", "default": "admin" },
+        "TIMEOUT": { "type": "duration", "max": "1m", "default": "30s" }
+    }"#).unwrap();
+    
+    let (compiled, issues) = lint(schema);
+    assert!(issues.is_empty());
+    assert_eq!(compiled.patterns().len(), 1);
+}
+
+#[test]
+fn test_load_rejects_lint_errors_and_keeps_warnings() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    
+    fs::write(&path, r#"{ "PORT": { "type": "number", "minimum": 1024, "default": 80 } }"#).unwrap();
+    match load_schema(&path).unwrap_err() {
+        SchemaError::LintErrors(file, issues) => {
+            assert_eq!(file, path);
+            assert_eq!(issues.len(), 1);
+            assert!(matches!(&issues[0], LintIssue::InvalidDefault(key, _) if key == "PORT"));
+        }
+        err => panic!("Expected LintErrors, got {}", err),
+    }
+    
+    fs::write(&path, r#"{ "C": { "type": "number", "constraints": [{ "expr": "C <<= 1" }] } }"#).unwrap();
+    match load_schema(&path).unwrap_err() {
+        SchemaError::LintErrors(_, issues) => {
+            assert!(matches!(&issues[..], [LintIssue::Compile(CompileError::InvalidExpression(key, ..))] if key == "C"));
+        }
+        err => panic!("Expected LintErrors, got {}", err),
+    }
+    
+    fs::write(&path, r#"{ "PORT": { "type": "number", "required": true, "default": 8080 } }"#).unwrap();
+    let loaded = load_schema_with_locations(&path).unwrap();
+    assert!(matches!(&loaded.issues[..], [LintIssue::RequiredWithDefault(key)] if key == "PORT"));
+}
+
+#[test]
+fn test_lint_schema_command() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, "{\n  \"PORT\": { \"type\": \"number\", \"required\": true, \"default\": 8080 },\n  \"EMAIL\": { \"type\": \"string\", \"pattern\": \"[a-z++\" }\n}").unwrap();
+    
+    let output = run_dotenvcrab(&["lint-schema", "--schema", path.to_str().unwrap()]);
+    
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("{}:3:3: error: invalid regex pattern for EMAIL", path.display())), "{}", stdout);
+    assert!(stdout.contains(&format!("{}:2:3: warning: PORT is required but has a default", path.display())), "{}", stdout);
+    
+    fs::write(&path, r#"{ "PORT": { "type": "number", "required": true } }"#).unwrap();
+    let output = run_dotenvcrab(&["lint-schema", "--schema", path.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No problems found"));
+}
@@ -0,0 +1,400 @@
+use dotenvcrab::loader::{
+    load_env_file, load_env_files, resolve_env_files, scope_env, LoadError,
+};
+use dotenvcrab::validation::ValidationError;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+mod test_helpers;
+use test_helpers::{
+    create_temp_file, load_schema_from_str, run_dotenvcrab, run_dotenvcrab_with_env,
+};
+
+#[test]
+fn test_load_env_file() {
+    let file = create_temp_file("PORT=8080\n# comment\nNAME=\"crab\"\n");
+    
+    let env_vars = load_env_file(file.path()).unwrap();
+    
+    assert_eq!(env_vars.len(), 2);
+    assert_eq!(env_vars["PORT"], "8080");
+    assert_eq!(env_vars["NAME"], "crab");
+}
+
+#[test]
+fn test_load_env_file_missing() {
+    let dir = tempdir().unwrap();
+    let err = load_env_file(dir.path().join("missing.env")).unwrap_err();
+    
+    assert!(matches!(err, LoadError::ReadError(..)));
+    assert!(err.to_string().starts_with("failed to load .env file"));
+}
+
+#[test]
+fn test_scope_env() {
+    let schema = load_schema_from_str(r#"{ "PORT": { "type": "number" } }"#).unwrap();
+    
+    let env_vars: HashMap<String, String> = [
+        ("PORT", "8080"),
+        ("APP_NAME", "crab"),
+        ("APP_DEBUG", "true"),
+        ("SENTRY_DSN", "https://sentry"),
+        ("PATH", "/usr/bin"),
+        ("HOME", "/root"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    
+    let scoped = scope_env(
+        env_vars,
+        &schema,
+        &["APP_".to_string()],
+        &["SENTRY_DSN".to_string()],
+    );
+    
+    let mut keys: Vec<&String> = scoped.keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["APP_DEBUG", "APP_NAME", "PORT", "SENTRY_DSN"]);
+}
+
+#[test]
+fn test_validate_process_env_cli() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    fs::write(&schema_path, r#"{
+        "CRAB_PORT": { "type": "number", "required": true },
+        "CRAB_DEBUG": { "type": "boolean" }
+    }"#).unwrap();
+    let schema = schema_path.to_str().unwrap();
+    
+    let output = run_dotenvcrab_with_env(
+        &["--from-process-env", "--schema", schema, "--strict", "--prefix", "CRAB_"],
+        &[("CRAB_PORT", "8080")],
+    );
+    assert!(output.status.success());
+    
+    let output = run_dotenvcrab_with_env(
+        &["--from-process-env", "--schema", schema, "--strict", "--prefix", "CRAB_"],
+        &[("CRAB_PORT", "8080"), ("CRAB_DEBGU", "true")],
+    );
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("CRAB_DEBGU"));
+    assert!(stdout.contains("not in schema"));
+    assert!(!stdout.contains("PATH"));
+    
+    let output = run_dotenvcrab_with_env(
+        &["--from-process-env", "--schema", schema],
+        &[("CRAB_PORT", "eighty")],
+    );
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("expected number"));
+}
+
+#[test]
+fn test_process_env_strict_requires_scope() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    fs::write(&schema_path, "{}").unwrap();
+    
+    let output = run_dotenvcrab_with_env(
+        &["--from-process-env", "--schema", schema_path.to_str().unwrap(), "--strict"],
+        &[],
+    );
+    
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("requires --prefix or --allow"));
+}
+
+#[test]
+fn test_parse_values() {
+    let file = create_temp_file(concat!(
+        "export EXPORTED=yes\n",
+        "UNQUOTED = hello world   # trailing comment\n",
+        "HASH=abc#def\n",
+        "SINGLE='$NOT_EXPANDED \\n'\n",
+        "DOUBLE=\"line1\\nline2 \\\"q\\\"\" # comment\n",
+        "MULTI=\"first\n",
+        "second\"\n",
+        "EMPTY=\n",
+        "REF=${UNQUOTED}!\n",
+        "BARE_REF=$EXPORTED-x\n",
+    ));
+    
+    let env_vars = load_env_file(file.path()).unwrap();
+    
+    assert_eq!(env_vars["EXPORTED"], "yes");
+    assert_eq!(env_vars["UNQUOTED"], "hello world");
+    assert_eq!(env_vars["HASH"], "abc#def");
+    assert_eq!(env_vars["SINGLE"], "$NOT_EXPANDED \\n");
+    assert_eq!(env_vars["DOUBLE"], "line1\nline2 \"q\"");
+    assert_eq!(env_vars["MULTI"], "first\nsecond");
+    assert_eq!(env_vars["EMPTY"], "");
+    assert_eq!(env_vars["REF"], "hello world!");
+    assert_eq!(env_vars["BARE_REF"], "yes-x");
+}
+
+#[test]
+fn test_parse_errors_report_line() {
+    let file = create_temp_file("GOOD=1\n\nBAD LINE\n");
+    
+    let err = load_env_file(file.path()).unwrap_err();
+    match &err {
+        LoadError::ParseError(location, _) => {
+            assert_eq!(location.line, 3);
+            assert_eq!(location.file, file.path());
+        }
+        _ => panic!("Expected ParseError"),
+    }
+    assert!(err.to_string().contains(&format!("{}:3", file.path().display())));
+    
+    let file = create_temp_file("A=\"unterminated\n");
+    assert!(load_env_file(file.path()).is_err());
+}
+
+#[test]
+fn test_layered_files_override_and_track_origin() {
+    let dir = tempdir().unwrap();
+    let base = dir.path().join(".env");
+    let local = dir.path().join(".env.local");
+    fs::write(&base, "PORT=8080\nHOST=localhost\nURL=http://${HOST}\n").unwrap();
+    fs::write(&local, "# overrides\nHOST=example.com\nFULL=${URL}:${PORT}\n").unwrap();
+    
+    let loaded = load_env_files(&[&base, &local]).unwrap();
+    
+    assert_eq!(loaded.vars["PORT"], "8080");
+    assert_eq!(loaded.vars["HOST"], "example.com");
+    assert_eq!(loaded.vars["URL"], "http://localhost");
+    assert_eq!(loaded.vars["FULL"], "http://localhost:8080");
+    
+    let port = loaded.origin("PORT").unwrap();
+    assert_eq!(port.file, base);
+    assert_eq!(port.line, 1);
+    
+    let host = loaded.origin("HOST").unwrap();
+    assert_eq!(host.file, local);
+    assert_eq!(host.line, 2);
+}
+
+#[test]
+fn test_resolve_env_files_mode_cascade() {
+    let dir = tempdir().unwrap();
+    let base = dir.path().join(".env");
+    fs::write(&base, "").unwrap();
+    fs::write(dir.path().join(".env.production"), "").unwrap();
+    fs::write(dir.path().join(".env.production.local"), "").unwrap();
+    fs::write(dir.path().join(".env.staging"), "").unwrap();
+    
+    let bases = vec![base.clone()];
+    let files = resolve_env_files(&bases, Some("production"));
+    assert_eq!(
+        files,
+        vec![
+            base.clone(),
+            dir.path().join(".env.production"),
+            dir.path().join(".env.production.local"),
+        ]
+    );
+    
+    assert_eq!(resolve_env_files(&bases, None), bases);
+    
+    let missing = vec![dir.path().join("missing.env")];
+    assert_eq!(resolve_env_files(&missing, Some("production")), missing);
+}
+
+#[test]
+fn test_layered_files_cli_reports_origin() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let base = dir.path().join(".env");
+    let production = dir.path().join(".env.production");
+    fs::write(&schema_path, r#"{ "PORT": { "type": "number" }, "DEBUG": { "type": "boolean" } }"#).unwrap();
+    fs::write(&base, "PORT=8080\nDEBUG=true\n").unwrap();
+    fs::write(&production, "\nPORT=eighty\n").unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "--env", base.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--mode", "production",
+    ]);
+    
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("expected number"));
+    assert!(stdout.contains(&format!("{}:2", production.display())));
+    
+    let output = run_dotenvcrab(&[
+        "--env", &format!("{},{}", production.display(), base.display()),
+        "--schema", schema_path.to_str().unwrap(),
+    ]);
+    
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_interpolation_defaults_and_forward_references() {
+    let file = create_temp_file(concat!(
+        "URL=http://${HOST}:${PORT:-8080}/${DB_NAME:-${APP}_db}\n",
+        "HOST=localhost\n",
+        "APP=crab\n",
+        "EMPTY=\n",
+        "WITH_EMPTY=${EMPTY:-fallback}\n",
+        "QUOTED=\"${HOST:-unused} \\${HOST}\"\n",
+        "LITERAL='${HOST}'\n",
+    ));
+    
+    let loaded = load_env_files(&[file.path()]).unwrap();
+    
+    assert!(loaded.errors.is_empty(), "{:?}", loaded.errors);
+    assert_eq!(loaded.vars["URL"], "http://localhost:8080/crab_db");
+    assert_eq!(loaded.vars["WITH_EMPTY"], "fallback");
+    assert_eq!(loaded.vars["QUOTED"], "localhost ${HOST}");
+    assert_eq!(loaded.vars["LITERAL"], "${HOST}");
+}
+
+#[test]
+fn test_interpolation_quoted_fallbacks() {
+    let file = create_temp_file(concat!(
+        "HOST=localhost\n",
+        "DOUBLE=${DOTENVCRAB_TEST_UNDEFINED:-\"two words\"}\n",
+        "SINGLE=${DOTENVCRAB_TEST_UNDEFINED:-'${HOST} } stays'}\n",
+        "EXPANDED=${DOTENVCRAB_TEST_UNDEFINED:-\"$HOST:\\\"8080\\\"\"}\n",
+        "MIXED=${DOTENVCRAB_TEST_UNDEFINED:-a\"b\"'c'}\n",
+        "IN_QUOTES=\"${DOTENVCRAB_TEST_UNDEFINED:-\"x y\"}\"\n",
+    ));
+    
+    let loaded = load_env_files(&[file.path()]).unwrap();
+    
+    assert!(loaded.errors.is_empty(), "{:?}", loaded.errors);
+    assert_eq!(loaded.vars["DOUBLE"], "two words");
+    assert_eq!(loaded.vars["SINGLE"], "${HOST} } stays");
+    assert_eq!(loaded.vars["EXPANDED"], "localhost:\"8080\"");
+    assert_eq!(loaded.vars["MIXED"], "abc");
+    assert_eq!(loaded.vars["IN_QUOTES"], "x y");
+    
+    let file = create_temp_file("A=${B:-\"unterminated}\n");
+    match load_env_files(&[file.path()]).unwrap_err() {
+        LoadError::ParseError(_, message) => assert_eq!(message, "unterminated quote in ${...} reference"),
+        err => panic!("Expected ParseError, got {}", err),
+    }
+}
+
+#[test]
+fn test_interpolation_operators_without_colon() {
+    let file = create_temp_file(concat!(
+        "EMPTY=\n",
+        "SET=value\n",
+        "DEFAULT_EMPTY=${EMPTY-fallback}\n",
+        "DEFAULT_UNSET=${DOTENVCRAB_TEST_UNDEFINED-fallback}\n",
+        "ALT_EMPTY=${EMPTY+alt}|${EMPTY:+alt}\n",
+        "ALT_SET=${SET+alt}|${SET:+alt $SET}\n",
+        "ALT_UNSET=${DOTENVCRAB_TEST_UNDEFINED:+alt}\n",
+        "REQUIRED_EMPTY=${EMPTY?not reported}\n",
+    ));
+    
+    let loaded = load_env_files(&[file.path()]).unwrap();
+    
+    assert!(loaded.errors.is_empty(), "{:?}", loaded.errors);
+    assert_eq!(loaded.vars["DEFAULT_EMPTY"], "");
+    assert_eq!(loaded.vars["DEFAULT_UNSET"], "fallback");
+    assert_eq!(loaded.vars["ALT_EMPTY"], "alt|");
+    assert_eq!(loaded.vars["ALT_SET"], "alt|alt value");
+    assert_eq!(loaded.vars["ALT_UNSET"], "");
+    assert_eq!(loaded.vars["REQUIRED_EMPTY"], "");
+}
+
+#[test]
+fn test_interpolation_unsupported_operators_are_reported_per_key() {
+    let file = create_temp_file(concat!(
+        "A=${B:=x}\n",
+        "C=${B=x}\n",
+        "D=${B:}\n",
+        "OK=fine\n",
+    ));
+    
+    let loaded = load_env_files(&[file.path()]).unwrap();
+    
+    assert_eq!(loaded.vars["OK"], "fine");
+    let reported: Vec<(&str, &str)> = loaded
+        .errors
+        .iter()
+        .map(|error| match error {
+            ValidationError::UnsupportedExpansion(key, name, operator) => {
+                assert_eq!(name, "B");
+                (key.as_str(), operator.as_str())
+            }
+            error => panic!("Expected UnsupportedExpansion, got {:?}", error),
+        })
+        .collect();
+    assert_eq!(reported, [("A", ":="), ("C", "="), ("D", ":")]);
+    assert_eq!(loaded.errors[0].to_string(), "A references B with unsupported expansion :=");
+}
+
+#[test]
+fn test_interpolation_undefined_and_required_references() {
+    let file = create_temp_file(concat!(
+        "A=${DOTENVCRAB_TEST_UNDEFINED}x\n",
+        "B=${DOTENVCRAB_TEST_UNDEFINED:?set it in .env.local}\n",
+    ));
+    
+    let loaded = load_env_files(&[file.path()]).unwrap();
+    
+    assert_eq!(loaded.vars["A"], "x");
+    assert_eq!(loaded.vars["B"], "");
+    match &loaded.errors[..] {
+        [
+            ValidationError::UndefinedReference(key, name),
+            ValidationError::UnsetReference(required_key, required_name, message),
+        ] => {
+            assert_eq!(key, "A");
+            assert_eq!(name, "DOTENVCRAB_TEST_UNDEFINED");
+            assert_eq!(required_key, "B");
+            assert_eq!(required_name, "DOTENVCRAB_TEST_UNDEFINED");
+            assert_eq!(message, "set it in .env.local");
+        }
+        errors => panic!("Expected reference errors, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_interpolation_detects_cycles() {
+    let file = create_temp_file("A=${B}\nB=x${C}\nC=${A}\nD=${D}\n");
+    
+    let loaded = load_env_files(&[file.path()]).unwrap();
+    
+    match &loaded.errors[..] {
+        [ValidationError::CyclicReference(key, cycle), ValidationError::UndefinedReference(..)] => {
+            assert_eq!(key, "C");
+            assert_eq!(cycle, &vec!["A", "B", "C", "A"]);
+        }
+        errors => panic!("Expected CyclicReference error, got {:?}", errors),
+    }
+    assert_eq!(
+        loaded.errors[0].to_string(),
+        "C has a cyclic reference: A -> B -> C -> A"
+    );
+    assert_eq!(loaded.vars["A"], "x");
+}
+
+#[test]
+fn test_interpolation_errors_fail_validation_cli() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let env_path = dir.path().join(".env");
+    fs::write(&schema_path, r#"{ "PORT": { "type": "number" } }"#).unwrap();
+    fs::write(&env_path, "BASE=80\nPORT=${BASE}${DOTENVCRAB_TEST_UNDEFINED}\n").unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+    ]);
+    
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("references undefined variable DOTENVCRAB_TEST_UNDEFINED"), "{}", stdout);
+    assert!(!stdout.contains("expected number"), "{}", stdout);
+}
@@ -0,0 +1,126 @@
+use dotenvcrab::loader::load_env_files;
+use dotenvcrab::location::{Location, SourceMap};
+use dotenvcrab::document::locate_keys;
+use dotenvcrab::schema::load_schema_with_locations;
+use dotenvcrab::validation::{validate_env, ValidationError};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+mod test_helpers;
+use test_helpers::run_dotenvcrab;
+
+#[test]
+fn test_location_display() {
+    let location = Location::new(".env", 3, 7);
+    assert_eq!(location.to_string(), ".env:3:7");
+}
+
+#[test]
+fn test_locate_keys() {
+    let content = r#"{
+  "PORT": { "type": "number", "required": true },
+    "ENV": {
+        "type": "enum",
+        "values": ["dev", "prod"],
+        "default": "dev"
+    }, "ESCAPED\"KEY": { "type": "string" }
+}"#;
+    
+    let locations = locate_keys(content, Path::new("env.schema.json"));
+    
+    assert_eq!(locations.len(), 3);
+    assert_eq!(locations["PORT"], Location::new("env.schema.json", 2, 3));
+    assert_eq!(locations["ENV"], Location::new("env.schema.json", 3, 5));
+    assert_eq!(locations["ESCAPED\"KEY"], Location::new("env.schema.json", 7, 8));
+}
+
+#[test]
+fn test_env_locations_have_columns() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(".env");
+    fs::write(&path, "PORT=1\n  HOST=x\nexport  NAME=y\n").unwrap();
+    
+    let loaded = load_env_files(&[&path]).unwrap();
+    
+    assert_eq!(loaded.origin("PORT"), Some(&Location::new(&path, 1, 1)));
+    assert_eq!(loaded.origin("HOST"), Some(&Location::new(&path, 2, 3)));
+    assert_eq!(loaded.origin("NAME"), Some(&Location::new(&path, 3, 9)));
+}
+
+#[test]
+fn test_error_locations() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let env_path = dir.path().join(".env");
+    fs::write(&schema_path, r#"{
+    "PORT": { "type": "number", "required": true },
+    "API_KEY": { "type": "string", "required": true }
+}"#).unwrap();
+    fs::write(&env_path, "PORT=abc\nEXTRA=1\n").unwrap();
+    
+    let schema = load_schema_with_locations(&schema_path).unwrap();
+    let env = load_env_files(&[&env_path]).unwrap();
+    let result = validate_env(&env.vars, &schema.schema, true);
+    let sources = SourceMap {
+        env: env.origins,
+        schema: schema.locations,
+    };
+    
+    assert_eq!(result.errors.len(), 3);
+    for error in &result.errors {
+        let location = error.location(&sources).unwrap();
+        match error {
+            ValidationError::InvalidType(..) => {
+                assert_eq!(location, &Location::new(&env_path, 1, 1));
+            }
+            ValidationError::ExtraField(_) => {
+                assert_eq!(location, &Location::new(&env_path, 2, 1));
+            }
+            ValidationError::MissingRequired(_) => {
+                assert_eq!(location, &Location::new(&schema_path, 3, 5));
+            }
+            _ => panic!("Unexpected error {:?}", error),
+        }
+    }
+}
+
+#[test]
+fn test_cli_output_includes_locations() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let env_path = dir.path().join(".env");
+    fs::write(&schema_path, r#"{
+    "PORT": { "type": "number", "required": true },
+    "API_KEY": { "type": "string", "required": true }
+}"#).unwrap();
+    fs::write(&env_path, "# config\nPORT=abc\n").unwrap();
+    
+    let args = [
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+    ];
+    
+    let output = run_dotenvcrab(&args);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("{}:2:1", env_path.display())));
+    assert!(stdout.contains(&format!("{}:3:5", schema_path.display())));
+    
+    let output = run_dotenvcrab(&[&args[..], &["--json"]].concat());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let errors: Vec<&str> = json["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e.as_str().unwrap())
+        .collect();
+    assert!(errors.contains(&format!(
+        "{}:2:1: invalid type for PORT: expected number, got abc",
+        env_path.display()
+    ).as_str()));
+    assert!(errors.contains(&format!(
+        "{}:3:5: missing required field: API_KEY",
+        schema_path.display()
+    ).as_str()));
+}
@@ -0,0 +1,113 @@
+use dotenvcrab::validation::ValidationError;
+mod test_helpers;
+use test_helpers::validate_env_with_schema;
+
+const PORT_SCHEMA: &str = r#"{
+    "PORT": { "type": "number", "required": true, "integer": true, "minimum": 1, "maximum": 65535 }
+}"#;
+
+#[test]
+fn test_number_bounds_valid() {
+    for env_str in ["PORT=1", "PORT=8080", "PORT=65535"] {
+        let result = validate_env_with_schema(env_str, PORT_SCHEMA, false);
+        assert!(result.is_valid, "{} should be valid: {:?}", env_str, result.errors);
+    }
+}
+
+#[test]
+fn test_number_below_minimum() {
+    let result = validate_env_with_schema("PORT=0", PORT_SCHEMA, false);
+    assert_eq!(result.errors.len(), 1);
+    
+    match &result.errors[0] {
+        ValidationError::BelowMinimum(key, min, got) => {
+            assert_eq!(key, "PORT");
+            assert_eq!(*min, 1.0);
+            assert_eq!(got, "0");
+        }
+        _ => panic!("Expected BelowMinimum error, got {:?}", result.errors[0]),
+    }
+    assert_eq!(result.errors[0].to_string(), "value for PORT must be at least 1, got 0");
+}
+
+#[test]
+fn test_number_above_maximum() {
+    let result = validate_env_with_schema("PORT=999999", PORT_SCHEMA, false);
+    assert_eq!(result.errors.len(), 1);
+    
+    match &result.errors[0] {
+        ValidationError::AboveMaximum(key, max, got) => {
+            assert_eq!(key, "PORT");
+            assert_eq!(*max, 65535.0);
+            assert_eq!(got, "999999");
+        }
+        _ => panic!("Expected AboveMaximum error, got {:?}", result.errors[0]),
+    }
+}
+
+#[test]
+fn test_number_integer() {
+    let result = validate_env_with_schema("PORT=-3.7", PORT_SCHEMA, false);
+    assert_eq!(result.errors.len(), 2);
+    
+    assert!(matches!(&result.errors[0], ValidationError::NotInteger(key, got) if key == "PORT" && got == "-3.7"));
+    assert!(matches!(&result.errors[1], ValidationError::BelowMinimum(..)));
+    
+    let result = validate_env_with_schema("PORT=80.0", PORT_SCHEMA, false);
+    assert!(result.is_valid);
+}
+
+#[test]
+fn test_number_exclusive_bounds() {
+    let schema_str = r#"{
+        "RATIO": { "type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 1 }
+    }"#;
+    
+    assert!(validate_env_with_schema("RATIO=0.5", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("RATIO=0", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::BelowExclusiveMinimum(_, min, _)] if *min == 0.0));
+    
+    let result = validate_env_with_schema("RATIO=1", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::AboveExclusiveMaximum(_, max, _)] if *max == 1.0));
+}
+
+#[test]
+fn test_number_multiple_of() {
+    let schema_str = r#"{
+        "TIMEOUT": { "type": "number", "multipleOf": 0.1 },
+        "WORKERS": { "type": "number", "multipleOf": 2 }
+    }"#;
+    
+    assert!(validate_env_with_schema("TIMEOUT=0.3\nWORKERS=8", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("TIMEOUT=0.35\nWORKERS=8", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::NotMultipleOf(key, ..)] if key == "TIMEOUT"));
+    
+    let result = validate_env_with_schema("TIMEOUT=1\nWORKERS=3", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::NotMultipleOf(key, step, got)] if key == "WORKERS" && *step == 2.0 && got == "3"));
+}
+
+#[test]
+fn test_number_type_error_skips_bounds() {
+    let result = validate_env_with_schema("PORT=abc", PORT_SCHEMA, false);
+    assert_eq!(result.errors.len(), 1);
+    assert!(matches!(&result.errors[0], ValidationError::InvalidType(..)));
+}
+
+#[test]
+fn test_number_rejects_non_finite_values() {
+    let schema_str = r#"{ "RATIO": { "type": "number", "minimum": 1, "maximum": 100 } }"#;
+    
+    for value in ["NaN", "inf", "-inf", "infinity"] {
+        let result = validate_env_with_schema(&format!("RATIO={}", value), schema_str, false);
+        match &result.errors[..] {
+            [ValidationError::InvalidType(key, expected, got)] => {
+                assert_eq!(key, "RATIO");
+                assert_eq!(expected, "number");
+                assert_eq!(got, value);
+            }
+            errors => panic!("Expected InvalidType for {}, got {:?}", value, errors),
+        }
+    }
+}
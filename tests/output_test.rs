@@ -1,5 +1,14 @@
+use dotenvcrab::location::{Location, SourceMap};
+use dotenvcrab::output::{
+    render_junit_result, render_sarif_result, render_structured_result, JSON_FORMAT_VERSION,
+    SARIF_RULES,
+};
+use dotenvcrab::schema::StringFormat;
 use dotenvcrab::validation::{ValidationError, ValidationResult};
 
+mod test_helpers;
+use test_helpers::load_schema_from_str;
+
 #[test]
 fn test_validation_result_valid() {
     let result = ValidationResult {
@@ -89,3 +98,273 @@ fn test_validation_error_to_string() {
     let error = ValidationError::ExtraField("EXTRA".to_string());
     assert_eq!(error.to_string(), "extra field not in schema: EXTRA");
 }
+
+#[test]
+fn test_validation_error_codes() {
+    assert_eq!(ValidationError::MissingRequired("A".to_string()).code(), "missing_required");
+    assert_eq!(
+        ValidationError::InvalidType("A".to_string(), "number".to_string(), "x".to_string()).code(),
+        "invalid_type"
+    );
+    assert_eq!(
+        ValidationError::InvalidEnum("A".to_string(), vec![], "x".to_string()).code(),
+        "invalid_enum"
+    );
+    assert_eq!(ValidationError::ExtraField("A".to_string()).code(), "extra_field");
+    assert_eq!(
+        ValidationError::InvalidPattern("A".to_string(), "^a$".to_string(), "b".to_string()).code(),
+        "pattern_mismatch"
+    );
+    assert_eq!(
+        ValidationError::InvalidRegexPattern("A".to_string(), "oops".to_string()).code(),
+        "invalid_regex"
+    );
+}
+
+#[test]
+fn test_render_structured_result() {
+    let mut result = ValidationResult::new();
+    result.add_error(ValidationError::InvalidEnum(
+        "ENV".to_string(),
+        vec!["dev".to_string(), "prod".to_string()],
+        "test".to_string(),
+    ));
+    result.add_error(ValidationError::MissingRequired("API_KEY".to_string()));
+    
+    let mut sources = SourceMap::default();
+    sources.env.insert("ENV".to_string(), Location::new(".env", 2, 1));
+    
+    let json: serde_json::Value =
+        serde_json::from_str(&render_structured_result(&result, &sources)).unwrap();
+    
+    assert_eq!(json["version"], JSON_FORMAT_VERSION);
+    assert_eq!(json["valid"], false);
+    
+    let errors = json["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 2);
+    
+    assert_eq!(errors[0]["code"], "invalid_enum");
+    assert_eq!(errors[0]["key"], "ENV");
+    assert_eq!(errors[0]["expected"], serde_json::json!(["dev", "prod"]));
+    assert_eq!(errors[0]["actual"], "test");
+    assert_eq!(errors[0]["location"]["file"], ".env");
+    assert_eq!(errors[0]["location"]["line"], 2);
+    assert_eq!(errors[0]["location"]["column"], 1);
+    
+    assert_eq!(errors[1]["code"], "missing_required");
+    assert_eq!(errors[1]["key"], "API_KEY");
+    assert!(errors[1]["expected"].is_null());
+    assert!(errors[1]["actual"].is_null());
+    assert!(errors[1]["location"].is_null());
+    assert_eq!(errors[1]["message"], "missing required field: API_KEY");
+}
+
+#[test]
+fn test_render_structured_result_actual_is_the_value() {
+    let mut result = ValidationResult::new();
+    result.add_error(ValidationError::InvalidRegexPattern("NAME".to_string(), "unclosed group".to_string()));
+    result.add_error(ValidationError::InvalidPattern(
+        "CODE".to_string(),
+        "^[A-Z]{3}$".to_string(),
+        "abc".to_string(),
+    ));
+    result.add_error(ValidationError::InvalidUrl(
+        "API_URL".to_string(),
+        "not a url".to_string(),
+        "relative URL without a base".to_string(),
+    ));
+    result.add_error(ValidationError::JsonSchemaMismatch(
+        "FLAGS".to_string(),
+        "/beta".to_string(),
+        "expected boolean, got string".to_string(),
+        r#""yes""#.to_string(),
+    ));
+    result.add_error(ValidationError::UnsetReference(
+        "DATABASE_URL".to_string(),
+        "DB_NAME".to_string(),
+        "set DB_NAME in .env.local".to_string(),
+    ));
+    
+    let json: serde_json::Value =
+        serde_json::from_str(&render_structured_result(&result, &SourceMap::default())).unwrap();
+    let errors = json["errors"].as_array().unwrap();
+    
+    assert_eq!(errors[0]["code"], "invalid_regex");
+    assert!(errors[0]["actual"].is_null());
+    assert_eq!(errors[0]["message"], "invalid regex pattern for NAME: unclosed group");
+    
+    assert_eq!(errors[1]["code"], "pattern_mismatch");
+    assert_eq!(errors[1]["expected"], "^[A-Z]{3}$");
+    assert_eq!(errors[1]["actual"], "abc");
+    assert_eq!(errors[1]["message"], "value for CODE does not match pattern: ^[A-Z]{3}$");
+    
+    assert_eq!(errors[2]["code"], "invalid_url");
+    assert_eq!(errors[2]["expected"], "url");
+    assert_eq!(errors[2]["actual"], "not a url");
+    assert_eq!(errors[2]["message"], "value for API_URL is not a valid URL: relative URL without a base");
+    
+    assert_eq!(errors[3]["code"], "json_schema_mismatch");
+    assert_eq!(errors[3]["expected"], serde_json::json!({ "pointer": "/beta" }));
+    assert_eq!(errors[3]["actual"], r#""yes""#);
+    assert_eq!(
+        errors[3]["message"],
+        r#"JSON for FLAGS does not match schema at "/beta": expected boolean, got string"#
+    );
+    
+    assert_eq!(errors[4]["code"], "unset_reference");
+    assert_eq!(errors[4]["expected"], serde_json::json!({ "reference": "DB_NAME" }));
+    assert!(errors[4]["actual"].is_null());
+    assert!(errors[4]["message"].as_str().unwrap().contains("set DB_NAME in .env.local"));
+}
+
+#[test]
+fn test_render_structured_result_valid() {
+    let result = ValidationResult::new();
+    let json: serde_json::Value =
+        serde_json::from_str(&render_structured_result(&result, &SourceMap::default())).unwrap();
+    
+    assert_eq!(json["valid"], true);
+    assert!(json["errors"].as_array().unwrap().is_empty());
+}
+
+fn one_of_each_error() -> Vec<ValidationError> {
+    vec![
+        ValidationError::MissingRequired("A".to_string()),
+        ValidationError::InvalidType("A".to_string(), "number".to_string(), "x".to_string()),
+        ValidationError::InvalidEnum("A".to_string(), vec![], "x".to_string()),
+        ValidationError::ExtraField("A".to_string()),
+        ValidationError::InvalidPattern("A".to_string(), "^a$".to_string(), "b".to_string()),
+        ValidationError::InvalidRegexPattern("A".to_string(), "oops".to_string()),
+        ValidationError::NotInteger("A".to_string(), "1.5".to_string()),
+        ValidationError::BelowMinimum("A".to_string(), 1.0, "0".to_string()),
+        ValidationError::AboveMaximum("A".to_string(), 1.0, "2".to_string()),
+        ValidationError::BelowExclusiveMinimum("A".to_string(), 1.0, "1".to_string()),
+        ValidationError::AboveExclusiveMaximum("A".to_string(), 1.0, "1".to_string()),
+        ValidationError::NotMultipleOf("A".to_string(), 2.0, "3".to_string()),
+        ValidationError::TooShort("A".to_string(), 3, "ab".to_string()),
+        ValidationError::TooLong("A".to_string(), 1, "ab".to_string()),
+        ValidationError::InvalidFormat("A".to_string(), StringFormat::Email, "x".to_string()),
+        ValidationError::InvalidUrl("A".to_string(), "x".to_string(), "relative URL without a base".to_string()),
+        ValidationError::InvalidUrlScheme("A".to_string(), vec!["https".to_string()], "http".to_string()),
+        ValidationError::MissingUrlComponent("A".to_string(), "host".to_string()),
+        ValidationError::UrlCredentialsNotAllowed("A".to_string()),
+        ValidationError::TooFewItems("A".to_string(), 2, 1),
+        ValidationError::TooManyItems("A".to_string(), 2, 3),
+        ValidationError::DuplicateItem("A".to_string(), 1, "x".to_string()),
+        ValidationError::InvalidItem(
+            "A".to_string(),
+            0,
+            Box::new(ValidationError::NotInteger("A[0]".to_string(), "x".to_string())),
+        ),
+        ValidationError::InvalidJson("A".to_string(), "x".to_string(), "expected value".to_string()),
+        ValidationError::JsonSchemaMismatch("A".to_string(), "/a".to_string(), "x".to_string(), "1".to_string()),
+        ValidationError::TooSmall("A".to_string(), "1s".to_string(), "500ms".to_string()),
+        ValidationError::TooLarge("A".to_string(), "1GiB".to_string(), "2GiB".to_string()),
+        ValidationError::RequiredIf("A".to_string(), "B is set".to_string()),
+        ValidationError::RequiredUnless("A".to_string(), "B=x".to_string()),
+        ValidationError::DependentRequired("A".to_string(), "B".to_string()),
+        ValidationError::MutuallyExclusive("A".to_string(), "B".to_string()),
+        ValidationError::OneOfGroupMissing("A".to_string(), "g".to_string(), vec!["A".to_string()]),
+        ValidationError::OneOfGroupConflict("B".to_string(), "g".to_string(), vec!["A".to_string(), "B".to_string()]),
+        ValidationError::InvalidExpression("A".to_string(), "A <".to_string(), "syntax error".to_string()),
+        ValidationError::ConstraintFailed("A".to_string(), "A < B".to_string(), "A < B".to_string()),
+        ValidationError::UndefinedReference("A".to_string(), "B".to_string()),
+        ValidationError::CyclicReference("A".to_string(), vec!["A".to_string(), "B".to_string(), "A".to_string()]),
+        ValidationError::UnsetReference("A".to_string(), "B".to_string(), "B is needed".to_string()),
+        ValidationError::UnsupportedExpansion("A".to_string(), "B".to_string(), ":=".to_string()),
+    ]
+}
+
+#[test]
+fn test_sarif_rules_cover_every_error() {
+    let errors = one_of_each_error();
+    assert_eq!(SARIF_RULES.len(), errors.len());
+    
+    for error in &errors {
+        assert!(
+            SARIF_RULES.iter().any(|(id, _)| *id == error.code()),
+            "missing SARIF rule for {}",
+            error.code()
+        );
+    }
+}
+
+#[test]
+fn test_render_sarif_result() {
+    let mut result = ValidationResult::new();
+    result.add_error(ValidationError::InvalidType(
+        "PORT".to_string(),
+        "number".to_string(),
+        "abc".to_string(),
+    ));
+    result.add_error(ValidationError::ExtraField("EXTRA".to_string()));
+    
+    let mut sources = SourceMap::default();
+    sources.env.insert("PORT".to_string(), Location::new("config/.env", 4, 1));
+    
+    let sarif: serde_json::Value =
+        serde_json::from_str(&render_sarif_result(&result, &sources)).unwrap();
+    
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "dotenvcrab");
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), SARIF_RULES.len());
+    
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    
+    assert_eq!(results[0]["ruleId"], "invalid_type");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[0]["message"]["text"], "invalid type for PORT: expected number, got abc");
+    let rule_index = results[0]["ruleIndex"].as_u64().unwrap() as usize;
+    assert_eq!(run["tool"]["driver"]["rules"][rule_index]["id"], "invalid_type");
+    
+    let physical = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(physical["artifactLocation"]["uri"], "config/.env");
+    assert_eq!(physical["region"]["startLine"], 4);
+    assert_eq!(physical["region"]["startColumn"], 1);
+    
+    assert_eq!(results[1]["ruleId"], "extra_field");
+    assert!(results[1].get("locations").is_none());
+}
+
+#[test]
+fn test_render_junit_result() {
+    let schema = load_schema_from_str(r#"{
+        "PORT": { "type": "number", "required": true },
+        "ENV": { "type": "enum", "values": ["dev", "prod"] },
+        "NAME": { "type": "string" }
+    }"#).unwrap();
+    
+    let mut result = ValidationResult::new();
+    result.add_error(ValidationError::InvalidEnum(
+        "ENV".to_string(),
+        vec!["dev".to_string(), "prod".to_string()],
+        "<test>".to_string(),
+    ));
+    result.add_error(ValidationError::ExtraField("EXTRA".to_string()));
+    
+    let mut sources = SourceMap::default();
+    sources.env.insert("ENV".to_string(), Location::new(".env", 3, 1));
+    
+    let xml = render_junit_result(&result, &schema, &sources);
+    
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(xml.contains("<testsuites name=\"dotenvcrab\" tests=\"4\" failures=\"2\">"));
+    assert!(xml.contains("<testcase name=\"PORT\" classname=\"dotenvcrab\"/>"));
+    assert!(xml.contains("<testcase name=\"NAME\" classname=\"dotenvcrab\"/>"));
+    assert!(xml.contains(concat!(
+        "<testcase name=\"ENV\" classname=\"dotenvcrab\">\n",
+        "      <failure type=\"invalid_enum\" ",
+        "message=\"invalid enum value for ENV: expected one of [&quot;dev&quot;, &quot;prod&quot;], got &lt;test&gt;\">",
+        "expected: [dev, prod]\nactual: &lt;test&gt;\nlocation: .env:3:1</failure>\n",
+        "    </testcase>",
+    )));
+    assert!(xml.contains("<failure type=\"extra_field\" message=\"extra field not in schema: EXTRA\"></failure>"));
+    
+    let env_pos = xml.find("name=\"ENV\"").unwrap();
+    let extra_pos = xml.find("name=\"EXTRA\"").unwrap();
+    let name_pos = xml.find("name=\"NAME\"").unwrap();
+    let port_pos = xml.find("name=\"PORT\"").unwrap();
+    assert!(env_pos < extra_pos && extra_pos < name_pos && name_pos < port_pos);
+}
@@ -1,6 +1,7 @@
-use dotenvcrab::validation::ValidationError;
+use dotenvcrab::compiled::{CompileError, CompiledSchema};
+use dotenvcrab::validation::{validate_compiled, validate_env, ValidationError};
 mod test_helpers;
-use test_helpers::validate_env_with_schema;
+use test_helpers::{load_schema_from_str, parse_env_str, validate_env_with_schema};
 
 #[test]
 fn test_pattern_validation_valid() {
@@ -29,7 +30,7 @@ fn test_pattern_validation_invalid() {
     assert_eq!(result.errors.len(), 1);
     
     match &result.errors[0] {
-        ValidationError::InvalidPattern(key, pattern) => {
+        ValidationError::InvalidPattern(key, pattern, _) => {
             assert_eq!(key, "EMAIL");
             assert_eq!(pattern, r"^[^@\s]+@[^@\s]+\.[^@\s]+$");
         }
@@ -73,7 +74,7 @@ fn test_pattern_validation_multiple_fields() {
     assert_eq!(result.errors.len(), 1);
     
     match &result.errors[0] {
-        ValidationError::InvalidPattern(key, _) => {
+        ValidationError::InvalidPattern(key, ..) => {
             assert_eq!(key, "PHONE");
         }
         _ => panic!("Expected InvalidPattern error"),
@@ -98,7 +99,7 @@ fn test_pattern_validation_with_other_errors() {
     
     for error in &result.errors {
         match error {
-            ValidationError::InvalidPattern(key, _) => {
+            ValidationError::InvalidPattern(key, ..) => {
                 assert_eq!(key, "EMAIL");
                 has_pattern_error = true;
             }
@@ -114,3 +115,45 @@ fn test_pattern_validation_with_other_errors() {
     assert!(has_pattern_error);
     assert!(has_type_error);
 }
+
+#[test]
+fn test_compiled_schema_reuses_patterns() {
+    let schema = load_schema_from_str(r#"{
+        "EMAIL": { "type": "string", "required": true, "pattern": "^[^@]+@[^@]+$" },
+        "REPLY_TO": { "type": "string", "pattern": "^[^@]+@[^@]+$" },
+        "TAGS": { "type": "array", "items": { "type": "string", "pattern": "^[a-z]+$" } }
+    }"#).unwrap();
+    let (compiled, compile_errors) = CompiledSchema::compile(schema.clone());
+    assert!(compile_errors.is_empty());
+    assert_eq!(compiled.patterns().len(), 2);
+    
+    let messages = |errors: &[ValidationError]| {
+        let mut messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        messages.sort();
+        messages
+    };
+    for (env_str, error_count) in [("EMAIL=a@b\nTAGS=x,y", 0), ("EMAIL=nope\nREPLY_TO=also-nope\nTAGS=x,Y", 3)] {
+        let env_vars = parse_env_str(env_str).unwrap();
+        let compiled_errors = messages(&validate_compiled(&env_vars, &compiled, false).errors);
+        assert_eq!(compiled_errors.len(), error_count);
+        assert_eq!(compiled_errors, messages(&validate_env(&env_vars, &schema, false).errors));
+    }
+}
+
+#[test]
+fn test_compile_rejects_invalid_pattern() {
+    let schema = load_schema_from_str(r#"{
+        "NAME": { "type": "string" },
+        "TAGS": { "type": "array", "items": { "type": "string", "pattern": "[a-z++" } }
+    }"#).unwrap();
+    
+    let (compiled, errors) = CompiledSchema::compile(schema);
+    assert!(compiled.patterns().is_empty());
+    match &errors[..] {
+        [CompileError::InvalidPattern(key, error)] => {
+            assert_eq!(key, "TAGS");
+            assert!(error.contains("parse"), "{}", error);
+        }
+        errors => panic!("Expected one InvalidPattern, got {:?}", errors),
+    }
+}
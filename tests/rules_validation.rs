@@ -0,0 +1,145 @@
+use dotenvcrab::schema::Condition;
+use dotenvcrab::validation::ValidationError;
+mod test_helpers;
+use test_helpers::{load_schema_from_str, validate_env_with_schema};
+
+#[test]
+fn test_condition_deserialization() {
+    let schema = load_schema_from_str(r#"{
+        "SSL_CERT": { "type": "string", "requiredIf": "SSL_ENABLED" },
+        "S3_BUCKET": { "type": "string", "requiredIf": { "STORAGE": "s3" } }
+    }"#).unwrap();
+    
+    assert_eq!(
+        schema["SSL_CERT"].rules().required_if,
+        Some(Condition::Set("SSL_ENABLED".to_string()))
+    );
+    let condition = schema["S3_BUCKET"].rules().required_if.clone().unwrap();
+    assert_eq!(condition.to_string(), "STORAGE=s3");
+}
+
+#[test]
+fn test_required_if() {
+    let schema_str = r#"{
+        "STORAGE": { "type": "enum", "values": ["disk", "s3"], "default": "disk" },
+        "S3_BUCKET": { "type": "string", "requiredIf": { "STORAGE": "s3" } }
+    }"#;
+    
+    assert!(validate_env_with_schema("STORAGE=disk", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("STORAGE=s3\nS3_BUCKET=assets", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("STORAGE=s3", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::RequiredIf(key, condition)] => {
+            assert_eq!(key, "S3_BUCKET");
+            assert_eq!(condition, "STORAGE=s3");
+        }
+        errors => panic!("Expected RequiredIf error, got {:?}", errors),
+    }
+    assert_eq!(result.errors[0].to_string(), "S3_BUCKET is required when STORAGE=s3");
+}
+
+#[test]
+fn test_required_unless() {
+    let schema_str = r#"{
+        "DATABASE_URL": { "type": "url" },
+        "DATABASE_HOST": { "type": "string", "requiredUnless": "DATABASE_URL" }
+    }"#;
+    
+    assert!(validate_env_with_schema("DATABASE_URL=postgres://db/app", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("DATABASE_HOST=db", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("", schema_str, false);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(
+        result.errors[0].to_string(),
+        "DATABASE_HOST is required unless DATABASE_URL is set"
+    );
+}
+
+#[test]
+fn test_dependent_required() {
+    let schema_str = r#"{
+        "TLS_CERT": { "type": "string", "dependentRequired": ["TLS_KEY"] },
+        "TLS_KEY": { "type": "string", "dependentRequired": ["TLS_CERT"] }
+    }"#;
+    
+    assert!(validate_env_with_schema("", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("TLS_CERT=a.pem\nTLS_KEY=a.key", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("TLS_KEY=a.key", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::DependentRequired(key, trigger)] => {
+            assert_eq!(key, "TLS_CERT");
+            assert_eq!(trigger, "TLS_KEY");
+        }
+        errors => panic!("Expected DependentRequired error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_mutually_exclusive_reported_once() {
+    let schema_str = r#"{
+        "API_TOKEN": { "type": "string", "mutuallyExclusive": ["API_PASSWORD"] },
+        "API_PASSWORD": { "type": "string", "mutuallyExclusive": ["API_TOKEN"] }
+    }"#;
+    
+    assert!(validate_env_with_schema("API_TOKEN=t", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("API_TOKEN=t\nAPI_PASSWORD=p", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::MutuallyExclusive(key, other)] => {
+            assert_eq!(key, "API_PASSWORD");
+            assert_eq!(other, "API_TOKEN");
+        }
+        errors => panic!("Expected one MutuallyExclusive error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_one_of_group() {
+    let schema_str = r#"{
+        "AUTH_TOKEN": { "type": "string", "oneOfGroup": "auth" },
+        "AUTH_CERT": { "type": "string", "oneOfGroup": "auth" },
+        "AUTH_PASSWORD": { "type": "string", "oneOfGroup": "auth" }
+    }"#;
+    
+    assert!(validate_env_with_schema("AUTH_CERT=c", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::OneOfGroupMissing(key, group, members)] => {
+            assert_eq!(key, "AUTH_CERT");
+            assert_eq!(group, "auth");
+            assert_eq!(members, &vec!["AUTH_CERT", "AUTH_PASSWORD", "AUTH_TOKEN"]);
+        }
+        errors => panic!("Expected OneOfGroupMissing error, got {:?}", errors),
+    }
+    
+    let result = validate_env_with_schema("AUTH_TOKEN=t\nAUTH_CERT=c", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::OneOfGroupConflict(key, _, set)] => {
+            assert_eq!(key, "AUTH_TOKEN");
+            assert_eq!(set, &vec!["AUTH_CERT", "AUTH_TOKEN"]);
+        }
+        errors => panic!("Expected OneOfGroupConflict error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_one_of_group_counts_defaults() {
+    let schema_str = r#"{
+        "CACHE_MEMORY": { "type": "string", "oneOfGroup": "cache", "default": "64MiB" },
+        "CACHE_REDIS_URL": { "type": "string", "oneOfGroup": "cache" }
+    }"#;
+    
+    let result = validate_env_with_schema("", schema_str, false);
+    assert!(result.is_valid, "{:?}", result.errors);
+    
+    let result = validate_env_with_schema("CACHE_REDIS_URL=redis://cache", schema_str, false);
+    assert!(result.is_valid, "{:?}", result.errors);
+    
+    let result = validate_env_with_schema("CACHE_MEMORY=1GiB\nCACHE_REDIS_URL=redis://cache", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::OneOfGroupConflict(..)]));
+}
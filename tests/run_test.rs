@@ -0,0 +1,151 @@
+use dotenvcrab::run::{normalize, with_defaults};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+mod test_helpers;
+use test_helpers::{load_schema_from_str, run_dotenvcrab};
+
+const SCHEMA: &str = r#"{
+    "PORT": { "type": "number", "required": true },
+    "LOG_LEVEL": { "type": "enum", "values": ["debug", "info"], "default": "info" },
+    "NAME": { "type": "string" }
+}"#;
+
+#[test]
+fn test_with_defaults_fills_absent_keys() {
+    let schema = load_schema_from_str(SCHEMA).unwrap();
+    
+    let mut env_vars = HashMap::new();
+    env_vars.insert("PORT".to_string(), "8080".to_string());
+    
+    let resolved = with_defaults(&env_vars, &schema);
+    
+    assert_eq!(resolved.len(), 2);
+    assert_eq!(resolved["PORT"], "8080");
+    assert_eq!(resolved["LOG_LEVEL"], "info");
+    assert!(!resolved.contains_key("NAME"));
+}
+
+#[test]
+fn test_with_defaults_keeps_present_values() {
+    let schema = load_schema_from_str(SCHEMA).unwrap();
+    
+    let mut env_vars = HashMap::new();
+    env_vars.insert("PORT".to_string(), "8080".to_string());
+    env_vars.insert("LOG_LEVEL".to_string(), "debug".to_string());
+    
+    let resolved = with_defaults(&env_vars, &schema);
+    
+    assert_eq!(resolved["LOG_LEVEL"], "debug");
+}
+
+#[test]
+fn test_normalize_converts_units() {
+    let schema = load_schema_from_str(r#"{
+        "TIMEOUT": { "type": "duration", "unit": "ms" },
+        "CACHE_SIZE": { "type": "bytesize", "unit": "MiB" },
+        "GRACE": { "type": "duration" }
+    }"#).unwrap();
+    
+    let mut env_vars = HashMap::new();
+    env_vars.insert("TIMEOUT".to_string(), "1m30s".to_string());
+    env_vars.insert("CACHE_SIZE".to_string(), "1GiB".to_string());
+    env_vars.insert("GRACE".to_string(), "5s".to_string());
+    env_vars.insert("OTHER".to_string(), "5s".to_string());
+    
+    let normalized = normalize(&env_vars, &schema);
+    
+    assert_eq!(normalized["TIMEOUT"], "90000");
+    assert_eq!(normalized["CACHE_SIZE"], "1024");
+    assert_eq!(normalized["GRACE"], "5s");
+    assert_eq!(normalized["OTHER"], "5s");
+}
+
+#[test]
+fn test_normalize_is_exact_for_fractional_values() {
+    let schema = load_schema_from_str(r#"{
+        "T": { "type": "duration", "unit": "ms" },
+        "HALF": { "type": "duration", "unit": "s" },
+        "SIZE": { "type": "bytesize", "unit": "KB" }
+    }"#).unwrap();
+    
+    let mut env_vars = HashMap::new();
+    env_vars.insert("T".to_string(), "0.7s".to_string());
+    env_vars.insert("HALF".to_string(), "1500ms".to_string());
+    env_vars.insert("SIZE".to_string(), "1.1MB".to_string());
+    
+    let normalized = normalize(&env_vars, &schema);
+    
+    assert_eq!(normalized["T"], "700");
+    assert_eq!(normalized["HALF"], "1.5");
+    assert_eq!(normalized["SIZE"], "1100");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_injects_validated_env() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let env_path = dir.path().join(".env");
+    fs::write(&schema_path, SCHEMA).unwrap();
+    fs::write(&env_path, "PORT=8080\n").unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "run",
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--",
+        "sh", "-c", "echo $PORT-$LOG_LEVEL; exit 3",
+    ]);
+    
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim(), "8080-info");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_does_not_start_on_invalid_env() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let env_path = dir.path().join(".env");
+    let marker = dir.path().join("started");
+    fs::write(&schema_path, SCHEMA).unwrap();
+    fs::write(&env_path, "PORT=not-a-number\n").unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "run",
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--",
+        "touch", marker.to_str().unwrap(),
+    ]);
+    
+    assert!(!output.status.success());
+    assert!(!marker.exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Invalid .env"));
+    assert!(stdout.contains("expected number"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_exports_exact_unit_values() {
+    let dir = tempdir().unwrap();
+    let schema_path = dir.path().join("env.schema.json");
+    let env_path = dir.path().join(".env");
+    fs::write(&schema_path, r#"{ "T": { "type": "duration", "unit": "ms" } }"#).unwrap();
+    fs::write(&env_path, "T=0.7s\n").unwrap();
+    
+    let output = run_dotenvcrab(&[
+        "run",
+        "--env", env_path.to_str().unwrap(),
+        "--schema", schema_path.to_str().unwrap(),
+        "--",
+        "sh", "-c", "echo $T",
+    ]);
+    
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "700");
+}
@@ -1,7 +1,9 @@
-use dotenvcrab::schema::{load_schema, SchemaField};
+use dotenvcrab::schema::{load_schema, load_schema_with_locations, FieldRules, SchemaError, SchemaField};
+use std::fs;
 use std::path::Path;
+use tempfile::tempdir;
 mod test_helpers;
-use test_helpers::{create_temp_file, load_schema_from_str};
+use test_helpers::{create_temp_file, load_schema_from_str, run_dotenvcrab};
 
 #[test]
 fn test_load_schema_success() {
@@ -70,6 +72,10 @@ fn test_schema_field_is_required() {
         default: None,
         description: None,
         pattern: None,
+        min_length: None,
+        max_length: None,
+        format: None,
+        rules: FieldRules::default(),
     };
     assert!(string_field.is_required());
     
@@ -77,6 +83,13 @@ fn test_schema_field_is_required() {
         required: false,
         default: None,
         description: None,
+        minimum: None,
+        maximum: None,
+        exclusive_minimum: None,
+        exclusive_maximum: None,
+        multiple_of: None,
+        integer: false,
+        rules: FieldRules::default(),
     };
     assert!(!number_field.is_required());
 }
@@ -88,6 +101,10 @@ fn test_schema_field_get_default() {
         default: Some("default".to_string()),
         description: None,
         pattern: None,
+        min_length: None,
+        max_length: None,
+        format: None,
+        rules: FieldRules::default(),
     };
     assert_eq!(string_field.get_default(), Some("default".to_string()));
     
@@ -95,6 +112,13 @@ fn test_schema_field_get_default() {
         required: false,
         default: Some(42.0),
         description: None,
+        minimum: None,
+        maximum: None,
+        exclusive_minimum: None,
+        exclusive_maximum: None,
+        multiple_of: None,
+        integer: false,
+        rules: FieldRules::default(),
     };
     assert_eq!(number_field.get_default(), Some("42".to_string()));
     
@@ -102,6 +126,7 @@ fn test_schema_field_get_default() {
         required: false,
         default: Some(true),
         description: None,
+        rules: FieldRules::default(),
     };
     assert_eq!(boolean_field.get_default(), Some("true".to_string()));
     
@@ -110,6 +135,7 @@ fn test_schema_field_get_default() {
         values: vec!["a".to_string(), "b".to_string()],
         default: Some("a".to_string()),
         description: None,
+        rules: FieldRules::default(),
     };
     assert_eq!(enum_field.get_default(), Some("a".to_string()));
     
@@ -118,6 +144,229 @@ fn test_schema_field_get_default() {
         default: None,
         description: None,
         pattern: None,
+        min_length: None,
+        max_length: None,
+        format: None,
+        rules: FieldRules::default(),
     };
     assert_eq!(no_default.get_default(), None);
 }
+
+#[test]
+fn test_schema_extends_and_overrides() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("shared")).unwrap();
+    fs::write(dir.path().join("shared/database.schema.json"), r#"{
+        "DATABASE_URL": { "type": "url", "required": true },
+        "DATABASE_POOL": { "type": "number", "default": 5 }
+    }"#).unwrap();
+    fs::write(dir.path().join("shared/logging.schema.json"), r#"{
+        "LOG_LEVEL": { "type": "enum", "values": ["debug", "info"], "default": "info" },
+        "DATABASE_POOL": { "type": "number", "default": 10 }
+    }"#).unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, r#"{
+        "$extends": ["shared/database.schema.json", "shared/logging.schema.json"],
+        "LOG_LEVEL": { "type": "enum", "values": ["debug", "info", "warn"] },
+        "PORT": { "type": "number" }
+    }"#).unwrap();
+    
+    let loaded = load_schema_with_locations(&path).unwrap();
+    let schema = &loaded.schema;
+    
+    assert_eq!(schema.len(), 4);
+    assert_eq!(schema["DATABASE_POOL"].get_default(), Some("10".to_string()));
+    match &schema["LOG_LEVEL"] {
+        SchemaField::Enum { values, default, .. } => {
+            assert_eq!(values.len(), 3);
+            assert_eq!(*default, None);
+        }
+        _ => panic!("Expected LOG_LEVEL to be an enum field"),
+    }
+    
+    let location = &loaded.locations["DATABASE_URL"];
+    assert!(location.file.ends_with("shared/database.schema.json"));
+    assert_eq!(location.line, 2);
+}
+
+#[test]
+fn test_schema_ref_with_local_overrides() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("shared.schema.json"), r#"{
+        "DATABASE": { "type": "url", "schemes": ["postgres"], "description": "primary database" }
+    }"#).unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, r#"{
+        "DATABASE_URL": { "$ref": "shared.schema.json#/DATABASE", "required": true },
+        "MISSING": { "$ref": "shared.schema.json#/NOPE" }
+    }"#).unwrap();
+    
+    let err = load_schema(&path).unwrap_err();
+    assert!(matches!(err, SchemaError::InvalidRef(ref key, ..) if key == "MISSING"), "{}", err);
+    
+    fs::write(&path, r#"{
+        "DATABASE_URL": { "$ref": "shared.schema.json#/DATABASE", "required": true }
+    }"#).unwrap();
+    let schema = load_schema(&path).unwrap();
+    match &schema["DATABASE_URL"] {
+        SchemaField::Url { required, schemes, description, .. } => {
+            assert!(*required);
+            assert_eq!(schemes, &vec!["postgres".to_string()]);
+            assert_eq!(description.as_deref(), Some("primary database"));
+        }
+        _ => panic!("Expected DATABASE_URL to be a url field"),
+    }
+}
+
+#[test]
+fn test_schema_cyclic_extends() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.schema.json"), r#"{ "$extends": "b.schema.json" }"#).unwrap();
+    fs::write(dir.path().join("b.schema.json"), r#"{ "$extends": "a.schema.json" }"#).unwrap();
+    
+    let err = load_schema(dir.path().join("a.schema.json")).unwrap_err();
+    match &err {
+        SchemaError::CyclicInclude(chain) => assert_eq!(chain.len(), 3),
+        _ => panic!("Expected CyclicInclude error, got {}", err),
+    }
+    
+    fs::write(dir.path().join("b.schema.json"), r#"{ "$extends": "missing.schema.json" }"#).unwrap();
+    let err = load_schema(dir.path().join("a.schema.json")).unwrap_err();
+    assert!(matches!(err, SchemaError::IncludeError(..)));
+}
+
+#[test]
+fn test_schema_invalid_field_reports_location() {
+    let file = create_temp_file("{\n  \"PORT\": { \"type\": \"number\" },\n  \"NAME\": { \"type\": \"text\" }\n}");
+    
+    let err = load_schema(file.path()).unwrap_err();
+    match &err {
+        SchemaError::InvalidField(location, key, _) => {
+            assert_eq!(key, "NAME");
+            assert_eq!(location.line, 3);
+        }
+        _ => panic!("Expected InvalidField error, got {}", err),
+    }
+}
+
+#[test]
+fn test_resolve_schema_command() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("base.schema.json"), r#"{ "PORT": { "type": "number" } }"#).unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, r#"{ "$extends": "base.schema.json", "NAME": { "type": "string" } }"#).unwrap();
+    
+    let output = run_dotenvcrab(&["resolve-schema", "--schema", path.to_str().unwrap()]);
+    
+    assert!(output.status.success());
+    let resolved: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(resolved["PORT"]["type"], "number");
+    assert_eq!(resolved["NAME"]["type"], "string");
+    assert!(resolved.get("$extends").is_none());
+}
+
+#[test]
+fn test_load_yaml_and_toml_schemas() {
+    let dir = tempdir().unwrap();
+    let yaml = dir.path().join("env.schema.yaml");
+    fs::write(&yaml, "# ports below 1024 need root\nPORT:\n  type: number\n  required: true\n  minimum: 1024\nLOG_LEVEL:\n  type: enum\n  values: [debug, info]\n  default: info\n").unwrap();
+    let toml = dir.path().join("env.schema.toml");
+    fs::write(&toml, "# ports below 1024 need root\n[PORT]\ntype = \"number\"\nrequired = true\nminimum = 1024\n[LOG_LEVEL]\ntype = \"enum\"\nvalues = [\"debug\", \"info\"]\ndefault = \"info\"\n").unwrap();
+    
+    for path in [&yaml, &toml] {
+        let loaded = load_schema_with_locations(path).unwrap();
+        assert_eq!(loaded.schema.len(), 2);
+        assert!(matches!(loaded.schema["PORT"], SchemaField::Number { required: true, minimum: Some(min), .. } if min == 1024.0));
+        assert_eq!(loaded.schema["LOG_LEVEL"].get_default(), Some("info".to_string()));
+        assert_eq!(loaded.locations["PORT"].line, 2);
+        assert_eq!(loaded.locations["LOG_LEVEL"].line, 6);
+    }
+}
+
+#[test]
+fn test_schema_parse_errors_have_line_and_column() {
+    let dir = tempdir().unwrap();
+    let cases = [
+        ("env.schema.json", "{\n  \"PORT\": { \"type\": \"number\" },\n  \"HOST\" { }\n}", 3),
+        ("env.schema.yml", "PORT:\n  type: number\n type: string\n", 3),
+        ("env.schema.toml", "[PORT]\ntype = \"number\"\nrequired = yes\n", 3),
+    ];
+    
+    for (name, content, line) in cases {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        match load_schema(&path).unwrap_err() {
+            SchemaError::ParseError(location, message) => {
+                assert_eq!(location.line, line, "{}: {}", name, message);
+                assert!(location.column > 0);
+                assert!(!message.contains(" at line "), "{}", message);
+            }
+            err => panic!("Expected ParseError for {}, got {}", name, err),
+        }
+    }
+}
+
+#[test]
+fn test_unknown_schema_key_suggests_closest_key() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, "{\n  \"PORT\": { \"type\": \"number\" },\n  \"EMAIL\": {\n    \"type\": \"string\",\n    \"requred\": true\n  }\n}").unwrap();
+    
+    match load_schema(&path).unwrap_err() {
+        SchemaError::UnknownKey(location, field, key, suggestion) => {
+            assert_eq!((field.as_str(), key.as_str()), ("EMAIL", "requred"));
+            assert_eq!(suggestion.as_deref(), Some("required"));
+            assert_eq!((location.line, location.column), (5, 5));
+        }
+        err => panic!("Expected UnknownKey, got {}", err),
+    }
+    
+    let cases = [
+        (r#"{ "NAME": { "type": "string", "patern": "^a" } }"#, "patern", Some("pattern")),
+        (r#"{ "PORT": { "type": "number", "max": 10 } }"#, "max", None),
+        (r#"{ "TAGS": { "type": "array", "items": { "type": "string", "maxlength": 3 } } }"#, "maxlength", Some("maxLength")),
+        (r#"{ "TIMEOUT": { "type": "duration", "pattern": "^a" } }"#, "pattern", None),
+        (r#"{ "POOL": { "type": "number", "constraints": [{ "expr": "POOL > 0", "mesage": "too small" }] } }"#, "mesage", Some("message")),
+    ];
+    for (content, unknown, expected) in cases {
+        fs::write(&path, content).unwrap();
+        match load_schema(&path).unwrap_err() {
+            SchemaError::UnknownKey(location, _, key, suggestion) => {
+                assert_eq!(key, unknown);
+                assert_eq!(suggestion.as_deref(), expected);
+                assert_eq!(location.line, 1);
+                assert_eq!(&content[location.column..location.column + key.len()], key);
+            }
+            err => panic!("Expected UnknownKey for {}, got {}", unknown, err),
+        }
+    }
+    
+    fs::write(dir.path().join("env.schema.yaml"), "PORT:\n  type: number\n  multipleof: 2\n").unwrap();
+    let err = load_schema(dir.path().join("env.schema.yaml")).unwrap_err();
+    assert!(err.to_string().ends_with("env.schema.yaml:3:3, did you mean multipleOf?"), "{}", err);
+}
+
+#[test]
+fn test_every_documented_key_is_accepted() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("env.schema.json");
+    fs::write(&path, r#"{
+        "A": { "type": "string", "required": false, "default": "abc", "description": "a", "pattern": "^[a-z]+$",
+               "minLength": 1, "maxLength": 5, "format": "hostname", "requiredIf": "B", "requiredUnless": "C",
+               "dependentRequired": ["B"], "mutuallyExclusive": ["C"], "oneOfGroup": "g",
+               "constraints": [{ "expr": "len(A) > 0" }] },
+        "B": { "type": "number", "minimum": 0, "maximum": 10, "exclusiveMinimum": -1, "exclusiveMaximum": 11,
+               "multipleOf": 1, "integer": true },
+        "C": { "type": "boolean", "default": true },
+        "D": { "type": "enum", "values": ["x", "y"] },
+        "E": { "type": "url", "schemes": ["https"], "requireHost": true, "requirePort": false,
+               "requirePath": false, "allowCredentials": false },
+        "F": { "type": "array", "separator": ";", "minItems": 1, "maxItems": 3, "uniqueItems": true,
+               "items": { "type": "number" } },
+        "G": { "type": "json", "schema": { "type": "object" } },
+        "H": { "type": "duration", "min": "1s", "max": "1h", "unit": "ms" },
+        "I": { "type": "bytesize", "min": "1KB", "max": "1GB", "unit": "MiB" }
+    }"#).unwrap();
+    
+    assert_eq!(load_schema(&path).unwrap().len(), 9);
+}
@@ -0,0 +1,148 @@
+use dotenvcrab::formats::{matches_format, parse_duration};
+use dotenvcrab::schema::StringFormat;
+use dotenvcrab::validation::ValidationError;
+use std::time::Duration;
+mod test_helpers;
+use test_helpers::validate_env_with_schema;
+
+#[test]
+fn test_string_length() {
+    let schema_str = r#"{
+        "USERNAME": { "type": "string", "minLength": 3, "maxLength": 8 }
+    }"#;
+    
+    assert!(validate_env_with_schema("USERNAME=crab", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("USERNAME=éèà", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("USERNAME=ab", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::TooShort(key, min, got)] => {
+            assert_eq!(key, "USERNAME");
+            assert_eq!(*min, 3);
+            assert_eq!(got, "ab");
+        }
+        errors => panic!("Expected TooShort error, got {:?}", errors),
+    }
+    assert_eq!(
+        result.errors[0].to_string(),
+        "value for USERNAME must be at least 3 characters long, got 2"
+    );
+    
+    let result = validate_env_with_schema("USERNAME=crabcrabcrab", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooLong(_, 8, _)]));
+}
+
+#[test]
+fn test_string_format_error() {
+    let schema_str = r#"{
+        "EMAIL": { "type": "string", "format": "email" },
+        "STARTED_AT": { "type": "string", "format": "date-time" }
+    }"#;
+    
+    let result = validate_env_with_schema(
+        "EMAIL=crab@example.com\nSTARTED_AT=2024-02-30T10:00:00Z",
+        schema_str,
+        false,
+    );
+    
+    match &result.errors[..] {
+        [ValidationError::InvalidFormat(key, format, got)] => {
+            assert_eq!(key, "STARTED_AT");
+            assert_eq!(*format, StringFormat::DateTime);
+            assert_eq!(got, "2024-02-30T10:00:00Z");
+        }
+        errors => panic!("Expected InvalidFormat error, got {:?}", errors),
+    }
+    assert_eq!(
+        result.errors[0].to_string(),
+        "invalid date-time for STARTED_AT: 2024-02-30T10:00:00Z"
+    );
+}
+
+#[test]
+fn test_unknown_format_is_rejected() {
+    let schema = test_helpers::load_schema_from_str(r#"{
+        "EMAIL": { "type": "string", "format": "e-mail" }
+    }"#);
+    assert!(schema.is_err());
+}
+
+#[test]
+fn test_builtin_formats() {
+    let cases: &[(StringFormat, &[&str], &[&str])] = &[
+        (
+            StringFormat::Url,
+            &["https://example.com/path?q=1", "postgres://user:pw@db:5432/app"],
+            &["example.com", "http//broken", ""],
+        ),
+        (
+            StringFormat::Email,
+            &["crab@example.com", "first.last+tag@sub.example.org"],
+            &["crab", "crab@", "@example.com", "crab@localhost", "cr ab@example.com"],
+        ),
+        (
+            StringFormat::Hostname,
+            &["localhost", "db-1.internal", "example.com."],
+            &["-db", "db_1", "a..b", ""],
+        ),
+        (
+            StringFormat::Ipv4,
+            &["127.0.0.1", "10.0.0.255"],
+            &["256.0.0.1", "10.0.0", "::1"],
+        ),
+        (
+            StringFormat::Ipv6,
+            &["::1", "2001:db8::8a2e:370:7334"],
+            &["127.0.0.1", "2001:db8:::1"],
+        ),
+        (
+            StringFormat::Uuid,
+            &["123e4567-e89b-12d3-a456-426614174000"],
+            &["123e4567e89b12d3a456426614174000", "123e4567-e89b-12d3-a456-42661417400g"],
+        ),
+        (
+            StringFormat::Port,
+            &["1", "8080", "65535"],
+            &["0", "65536", "-1", "http"],
+        ),
+        (
+            StringFormat::Duration,
+            &["30s", "250ms", "1h30m", "1.5h", "2d"],
+            &["30", "s", "5 minutes", "-1s", ""],
+        ),
+        (
+            StringFormat::DateTime,
+            &["2024-01-31T23:59:59Z", "2024-02-29T00:00:00.123+02:00", "2024-06-01 12:00:00z"],
+            &["2024-01-31", "2023-02-29T00:00:00Z", "2024-01-31T24:00:00Z", "2024-13-01T00:00:00Z"],
+        ),
+    ];
+    
+    for (format, valid, invalid) in cases {
+        for value in *valid {
+            assert!(matches_format(*format, value), "{} should be a valid {}", value, format);
+        }
+        for value in *invalid {
+            assert!(!matches_format(*format, value), "{} should not be a valid {}", value, format);
+        }
+    }
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+    assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+    assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+    assert_eq!(parse_duration("1m"), Some(Duration::from_secs(60)));
+    assert_eq!(parse_duration("m1"), None);
+}
+
+#[test]
+fn test_parse_duration_is_exact() {
+    assert_eq!(parse_duration("0.7s"), Some(Duration::from_millis(700)));
+    assert_eq!(parse_duration("1.5h0.1ms"), Some(Duration::from_nanos(5_400_000_100_000)));
+    assert_eq!(parse_duration("9007199254740993ns"), Some(Duration::from_nanos(9_007_199_254_740_993)));
+    assert_eq!(parse_duration("213503982334d0.000000001s"), Some(Duration::new(18_446_744_073_657_600, 1)));
+    assert_eq!(parse_duration("999999999999999d"), None);
+    assert_eq!(parse_duration(".s"), None);
+    assert_eq!(parse_duration("1.2.3s"), None);
+}
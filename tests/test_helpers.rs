@@ -1,10 +1,11 @@
+#![allow(dead_code)]
+
 use dotenvcrab::validation::{validate_env, ValidationResult};
 use dotenvcrab::schema::{Schema, SchemaField};
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
-use serde_json;
 
 /// parse environment variables from a string in the format KEY=VALUE
 /// 
@@ -120,3 +121,22 @@ pub fn run_dotenvcrab(args: &[&str]) -> std::process::Output {
         .output()
         .expect("Failed to execute command")
 }
+
+/// run the dotenvcrab CLI with extra variables in its process environment
+/// 
+/// # arguments
+/// * `args` - the arguments to pass to the CLI
+/// * `vars` - the environment variables to set for the process
+/// 
+/// # returns
+/// * `std::process::Output` - the output of the command
+pub fn run_dotenvcrab_with_env(args: &[&str], vars: &[(&str, &str)]) -> std::process::Output {
+    std::process::Command::new("cargo")
+        .arg("run")
+        .arg("--")
+        .args(args)
+        .envs(vars.iter().copied())
+        .current_dir(Path::new(env!("CARGO_MANIFEST_DIR")))
+        .output()
+        .expect("Failed to execute command")
+}
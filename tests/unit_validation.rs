@@ -0,0 +1,88 @@
+use dotenvcrab::formats::parse_byte_size;
+use dotenvcrab::validation::ValidationError;
+mod test_helpers;
+use test_helpers::{load_schema_from_str, validate_env_with_schema};
+
+#[test]
+fn test_parse_byte_size() {
+    assert_eq!(parse_byte_size("1024"), Some(1024));
+    assert_eq!(parse_byte_size("512MiB"), Some(512 * 1024 * 1024));
+    assert_eq!(parse_byte_size("2GB"), Some(2_000_000_000));
+    assert_eq!(parse_byte_size("1.5 KiB"), Some(1536));
+    assert_eq!(parse_byte_size("10kb"), Some(10_000));
+    assert_eq!(parse_byte_size("MiB"), None);
+    assert_eq!(parse_byte_size("10 bananas"), None);
+    assert_eq!(parse_byte_size(""), None);
+}
+
+#[test]
+fn test_parse_byte_size_is_exact() {
+    assert_eq!(parse_byte_size("1.1GiB"), Some(1_181_116_006));
+    assert_eq!(parse_byte_size("9007199254740993"), Some(9_007_199_254_740_993));
+    assert_eq!(parse_byte_size("18446744073709551615B"), Some(u64::MAX));
+    assert_eq!(parse_byte_size("18446744073709551616"), None);
+    assert_eq!(parse_byte_size("0.0000001KB"), Some(0));
+    assert_eq!(parse_byte_size("0.5"), Some(1));
+}
+
+#[test]
+fn test_duration_field() {
+    let schema_str = r#"{
+        "REQUEST_TIMEOUT": { "type": "duration", "min": "1s", "max": "5m" }
+    }"#;
+    
+    assert!(validate_env_with_schema("REQUEST_TIMEOUT=30s", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("REQUEST_TIMEOUT=1m30s", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("REQUEST_TIMEOUT=500ms", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::TooSmall(key, min, got)] => {
+            assert_eq!(key, "REQUEST_TIMEOUT");
+            assert_eq!(min, "1s");
+            assert_eq!(got, "500ms");
+        }
+        errors => panic!("Expected TooSmall error, got {:?}", errors),
+    }
+    assert_eq!(
+        result.errors[0].to_string(),
+        "value for REQUEST_TIMEOUT must be at least 1s, got 500ms"
+    );
+    
+    let result = validate_env_with_schema("REQUEST_TIMEOUT=1h", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooLarge(..)]));
+    
+    let result = validate_env_with_schema("REQUEST_TIMEOUT=soon", schema_str, false);
+    match &result.errors[..] {
+        [ValidationError::InvalidType(_, expected, _)] => assert_eq!(expected, "duration"),
+        errors => panic!("Expected InvalidType error, got {:?}", errors),
+    }
+}
+
+#[test]
+fn test_bytesize_field() {
+    let schema_str = r#"{
+        "MEMORY_LIMIT": { "type": "bytesize", "min": "256MiB", "max": "2GB" }
+    }"#;
+    
+    assert!(validate_env_with_schema("MEMORY_LIMIT=512MiB", schema_str, false).is_valid);
+    assert!(validate_env_with_schema("MEMORY_LIMIT=2GB", schema_str, false).is_valid);
+    
+    let result = validate_env_with_schema("MEMORY_LIMIT=2GiB", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooLarge(..)]));
+    
+    let result = validate_env_with_schema("MEMORY_LIMIT=128MB", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::TooSmall(..)]));
+    
+    let result = validate_env_with_schema("MEMORY_LIMIT=lots", schema_str, false);
+    assert!(matches!(&result.errors[..], [ValidationError::InvalidType(..)]));
+}
+
+#[test]
+fn test_invalid_bounds_rejected_at_load() {
+    let err = load_schema_from_str(r#"{ "TIMEOUT": { "type": "duration", "max": "forever" } }"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid duration: forever"), "{}", err);
+    
+    assert!(load_schema_from_str(r#"{ "SIZE": { "type": "bytesize", "min": "big" } }"#).is_err());
+    assert!(load_schema_from_str(r#"{ "SIZE": { "type": "bytesize", "unit": "parsecs" } }"#).is_err());
+}
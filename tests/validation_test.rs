@@ -1,4 +1,4 @@
-use dotenvcrab::schema::SchemaField;
+use dotenvcrab::schema::{FieldRules, SchemaField};
 use dotenvcrab::validation::{validate_env, ValidationResult, ValidationError};
 use std::collections::HashMap;
 mod test_helpers;
@@ -30,6 +30,13 @@ fn test_validate_env_all_valid() {
             required: true,
             default: None,
             description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -38,6 +45,7 @@ fn test_validate_env_all_valid() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -60,6 +68,13 @@ fn test_validate_env_missing_required() {
             required: true,
             default: None,
             description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -68,6 +83,7 @@ fn test_validate_env_missing_required() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -96,6 +112,13 @@ fn test_validate_env_with_default_value() {
             required: true,
             default: None,
             description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -105,6 +128,7 @@ fn test_validate_env_with_default_value() {
             values: vec!["debug".to_string(), "info".to_string()],
             default: Some("info".to_string()),
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -126,6 +150,13 @@ fn test_validate_env_invalid_number() {
             required: true,
             default: None,
             description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
         },
     );
     
@@ -156,6 +187,7 @@ fn test_validate_env_invalid_boolean() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -186,6 +218,7 @@ fn test_validate_env_valid_boolean_variations() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -194,6 +227,7 @@ fn test_validate_env_valid_boolean_variations() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -202,6 +236,7 @@ fn test_validate_env_valid_boolean_variations() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -210,6 +245,7 @@ fn test_validate_env_valid_boolean_variations() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -218,6 +254,7 @@ fn test_validate_env_valid_boolean_variations() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -226,6 +263,7 @@ fn test_validate_env_valid_boolean_variations() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -253,6 +291,7 @@ fn test_validate_env_invalid_enum() {
             values: vec!["dev".to_string(), "prod".to_string()],
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -283,6 +322,13 @@ fn test_validate_env_strict_mode() {
             required: true,
             default: None,
             description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
         },
     );
     
@@ -315,6 +361,13 @@ fn test_validate_env_multiple_errors() {
             required: true,
             default: None,
             description: None,
+            minimum: None,
+            maximum: None,
+            exclusive_minimum: None,
+            exclusive_maximum: None,
+            multiple_of: None,
+            integer: false,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -324,6 +377,7 @@ fn test_validate_env_multiple_errors() {
             values: vec!["dev".to_string(), "prod".to_string()],
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     schema.insert(
@@ -332,6 +386,7 @@ fn test_validate_env_multiple_errors() {
             required: true,
             default: None,
             description: None,
+            rules: FieldRules::default(),
         },
     );
     
@@ -373,3 +428,31 @@ fn test_validate_env_multiple_errors() {
     assert!(has_missing_required);
     assert!(has_extra_field);
 }
+
+#[test]
+fn test_validate_env_errors_in_key_order() {
+    let schema_str = r#"{
+        "ZONE": { "type": "enum", "values": ["eu", "us"] },
+        "PORT": { "type": "number" },
+        "DEBUG": { "type": "boolean", "required": true },
+        "ALPHA": { "type": "number" },
+        "MODE": { "type": "enum", "values": ["dev", "prod"] }
+    }"#;
+    let env_str = "ZONE=asia\nPORT=http\nALPHA=one\nMODE=staging\nZZ_EXTRA=1\nAA_EXTRA=1";
+    
+    for _ in 0..5 {
+        let result = test_helpers::validate_env_with_schema(env_str, schema_str, true);
+        let keys: Vec<&str> = result
+            .errors
+            .iter()
+            .map(|error| match error {
+                ValidationError::InvalidType(key, ..)
+                | ValidationError::InvalidEnum(key, ..)
+                | ValidationError::MissingRequired(key)
+                | ValidationError::ExtraField(key) => key.as_str(),
+                error => panic!("Unexpected error {:?}", error),
+            })
+            .collect();
+        assert_eq!(keys, ["ALPHA", "DEBUG", "MODE", "PORT", "ZONE", "AA_EXTRA", "ZZ_EXTRA"]);
+    }
+}